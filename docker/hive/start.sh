@@ -1,10 +1,13 @@
 # 1. Create the genesis file
 echo "Supplied genesis state:"
 cat /genesis.json
+# Katana runs with the chain id of the genesis, which Kakarot reports as its own
+chain_id=$(jq -r '.config.chainId' /genesis.json)
 mv /genesis.json /genesis/hive-genesis.json
 echo "Creating the genesis file..."
 KAKAROT_CONTRACTS_PATH="genesis/contracts" \
 	HIVE_GENESIS_PATH="genesis/hive-genesis.json" \
+	KATANA_CHAIN_ID="${chain_id}" \
 	GENESIS_OUTPUT="genesis.json" \
 	MANIFEST_OUTPUT="manifest.json" \
 	hive_genesis
@@ -12,8 +15,7 @@ mv /genesis/hive-genesis.json /hive-genesis.json && rm -fr /genesis
 
 # 2. Start Katana
 echo "Launching Katana..."
-chain_id=$(printf '%x' $(jq -r '.config.chainId' hive-genesis.json))
-RUST_LOG=warn katana --block-time 2000 --disable-fee --chain-id=0x$chain_id --genesis genesis.json &
+RUST_LOG=warn katana --block-time 2000 --disable-fee --chain-id=0x$(printf '%x' "${chain_id}") --genesis genesis.json &
 ###### 2.5. Await Katana to be healthy
 # Loop until the curl command succeeds
 until
@@ -0,0 +1,413 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use itertools::Itertools;
+use reth_primitives::{keccak256, Address, Bytes, B256, U256};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::eth_provider::error::EthProviderError;
+use crate::eth_provider::provider::EthProviderResult;
+
+/// Name of the domain type, see <https://eips.ethereum.org/EIPS/eip-712#definition-of-domainseparator>.
+pub const EIP712_DOMAIN: &str = "EIP712Domain";
+
+/// Typed data as taken by `eth_signTypedData_v4`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    pub primary_type: String,
+    #[serde(default)]
+    pub domain: serde_json::Map<String, Value>,
+    #[serde(default)]
+    pub message: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub r#type: String,
+}
+
+impl TypedData {
+    /// Parses the typed data, which wallets send either as a JSON object or as a JSON encoded string.
+    pub fn from_value(value: Value) -> EthProviderResult<Self> {
+        let value = match value {
+            Value::String(data) => serde_json::from_str(&data).map_err(invalid_typed_data)?,
+            value => value,
+        };
+        serde_json::from_value(value).map_err(invalid_typed_data)
+    }
+
+    /// Returns the hash to sign: `keccak256("\x19\x01" || domainSeparator || hashStruct(message))`.
+    pub fn hash(&self) -> EthProviderResult<B256> {
+        let mut data = vec![0x19, 0x01];
+        data.extend_from_slice(self.domain_separator()?.as_slice());
+        if self.primary_type != EIP712_DOMAIN {
+            data.extend_from_slice(self.hash_struct(&self.primary_type, &self.message)?.as_slice());
+        }
+        Ok(keccak256(data))
+    }
+
+    pub fn domain_separator(&self) -> EthProviderResult<B256> {
+        self.hash_struct(EIP712_DOMAIN, &Value::Object(self.domain.clone()))
+    }
+
+    /// Returns `keccak256(typeHash || encodeData(data))` for the struct type.
+    pub fn hash_struct(&self, r#type: &str, data: &Value) -> EthProviderResult<B256> {
+        let fields = self.fields(r#type)?;
+        let mut encoded = self.type_hash(r#type)?.to_vec();
+        for field in fields.iter() {
+            let value = data.get(&field.name).unwrap_or(&Value::Null);
+            encoded.extend_from_slice(self.encode_value(&field.r#type, value)?.as_slice());
+        }
+        Ok(keccak256(encoded))
+    }
+
+    pub fn type_hash(&self, r#type: &str) -> EthProviderResult<B256> {
+        Ok(keccak256(self.encode_type(r#type)?))
+    }
+
+    /// Encodes the type as `name(type1 name1,...)` followed by its dependencies sorted by name.
+    pub fn encode_type(&self, r#type: &str) -> EthProviderResult<String> {
+        self.fields(r#type)?;
+        let mut dependencies = BTreeSet::new();
+        self.find_dependencies(r#type, &mut dependencies);
+        dependencies.remove(r#type);
+
+        let mut encoded = String::new();
+        for name in std::iter::once(r#type).chain(dependencies.iter().map(String::as_str)) {
+            let fields = self.fields(name)?.iter().map(|field| format!("{} {}", field.r#type, field.name)).join(",");
+            encoded.push_str(&format!("{name}({fields})"));
+        }
+        Ok(encoded)
+    }
+
+    /// Returns the fields of the type. The domain type is derived
+    /// from the domain fields if it is not explicitly declared.
+    fn fields(&self, r#type: &str) -> EthProviderResult<std::borrow::Cow<'_, [TypedDataField]>> {
+        if let Some(fields) = self.types.get(r#type) {
+            return Ok(fields.as_slice().into());
+        }
+        if r#type == EIP712_DOMAIN {
+            let fields = [
+                ("name", "string"),
+                ("version", "string"),
+                ("chainId", "uint256"),
+                ("verifyingContract", "address"),
+                ("salt", "bytes32"),
+            ]
+            .into_iter()
+            .filter(|(name, _)| self.domain.contains_key(*name))
+            .map(|(name, r#type)| TypedDataField { name: name.to_string(), r#type: r#type.to_string() })
+            .collect::<Vec<_>>();
+            return Ok(fields.into());
+        }
+        Err(unknown_type(r#type))
+    }
+
+    fn find_dependencies(&self, r#type: &str, dependencies: &mut BTreeSet<String>) {
+        let Some(fields) = self.types.get(r#type) else { return };
+        if !dependencies.insert(r#type.to_string()) {
+            return;
+        }
+        for field in fields {
+            self.find_dependencies(base_type(&field.r#type), dependencies);
+        }
+    }
+
+    /// Encodes the value on 32 bytes. Dynamic values, arrays and structs are hashed.
+    fn encode_value(&self, r#type: &str, value: &Value) -> EthProviderResult<B256> {
+        if let Some(element_type) = r#type.strip_suffix(']').and_then(|t| t.rsplit_once('[')).map(|(t, _)| t) {
+            let values = value.as_array().ok_or_else(|| invalid_value(r#type, value))?;
+            let mut encoded = Vec::with_capacity(32 * values.len());
+            for value in values {
+                encoded.extend_from_slice(self.encode_value(element_type, value)?.as_slice());
+            }
+            return Ok(keccak256(encoded));
+        }
+
+        if self.types.contains_key(r#type) {
+            if value.is_null() {
+                return Ok(B256::ZERO);
+            }
+            return self.hash_struct(r#type, value);
+        }
+
+        match r#type {
+            "string" => Ok(keccak256(value.as_str().ok_or_else(|| invalid_value(r#type, value))?)),
+            "bytes" => Ok(keccak256(parse_bytes(r#type, value)?)),
+            "bool" => {
+                let value = value.as_bool().ok_or_else(|| invalid_value(r#type, value))?;
+                Ok(U256::from(u8::from(value)).into())
+            }
+            "address" => {
+                let address = value.as_str().and_then(|a| Address::from_str(a).ok());
+                Ok(address.ok_or_else(|| invalid_value(r#type, value))?.into_word())
+            }
+            _ => {
+                if let Some(size) = r#type.strip_prefix("bytes") {
+                    let size: usize = size.parse().map_err(|_| unknown_type(r#type))?;
+                    let bytes = parse_bytes(r#type, value)?;
+                    if !(1..=32).contains(&size) || bytes.len() > size {
+                        return Err(invalid_value(r#type, value));
+                    }
+                    return Ok(B256::right_padding_from(&bytes));
+                }
+                if let Some(bits) = r#type.strip_prefix("uint") {
+                    let bits = parse_bits(r#type, bits)?;
+                    let value = parse_integer(r#type, value)?;
+                    if value.is_negative || value.abs.bit_len() > bits {
+                        return Err(invalid_value(r#type, &value.into()));
+                    }
+                    return Ok(value.abs.into());
+                }
+                if let Some(bits) = r#type.strip_prefix("int") {
+                    let bits = parse_bits(r#type, bits)?;
+                    let value = parse_integer(r#type, value)?;
+                    // The magnitude of a negative value can reach 2^(bits - 1), 2^(bits - 1) - 1 otherwise.
+                    let magnitude = if value.is_negative { value.abs - U256::from(1) } else { value.abs };
+                    if magnitude.bit_len() > bits - 1 {
+                        return Err(invalid_value(r#type, &value.into()));
+                    }
+                    let encoded = if value.is_negative { U256::ZERO.wrapping_sub(value.abs) } else { value.abs };
+                    return Ok(encoded.into());
+                }
+                Err(unknown_type(r#type))
+            }
+        }
+    }
+}
+
+/// Returns the type of the elements of an array type, or the type itself.
+fn base_type(r#type: &str) -> &str {
+    r#type.split_once('[').map_or(r#type, |(base, _)| base)
+}
+
+fn parse_bits(r#type: &str, bits: &str) -> EthProviderResult<usize> {
+    if bits.is_empty() {
+        return Ok(256);
+    }
+    let bits: usize = bits.parse().map_err(|_| unknown_type(r#type))?;
+    if bits == 0 || bits > 256 || bits % 8 != 0 {
+        return Err(unknown_type(r#type));
+    }
+    Ok(bits)
+}
+
+/// Integer parsed from a JSON number or a decimal or hexadecimal string.
+struct Integer {
+    is_negative: bool,
+    abs: U256,
+}
+
+impl From<Integer> for Value {
+    fn from(value: Integer) -> Self {
+        Self::String(format!("{}{}", if value.is_negative { "-" } else { "" }, value.abs))
+    }
+}
+
+fn parse_integer(r#type: &str, value: &Value) -> EthProviderResult<Integer> {
+    match value {
+        Value::Number(number) => {
+            if let Some(n) = number.as_u64() {
+                Ok(Integer { is_negative: false, abs: U256::from(n) })
+            } else if let Some(n) = number.as_i64() {
+                Ok(Integer { is_negative: n < 0, abs: U256::from(n.unsigned_abs()) })
+            } else {
+                Err(invalid_value(r#type, value))
+            }
+        }
+        Value::String(s) => {
+            let (is_negative, abs) = s.strip_prefix('-').map_or((false, s.as_str()), |abs| (true, abs));
+            let abs = U256::from_str(abs).map_err(|_| invalid_value(r#type, value))?;
+            Ok(Integer { is_negative: is_negative && abs != U256::ZERO, abs })
+        }
+        _ => Err(invalid_value(r#type, value)),
+    }
+}
+
+fn parse_bytes(r#type: &str, value: &Value) -> EthProviderResult<Bytes> {
+    value.as_str().and_then(|b| Bytes::from_str(b).ok()).ok_or_else(|| invalid_value(r#type, value))
+}
+
+fn invalid_typed_data(err: serde_json::Error) -> EthProviderError {
+    EthProviderError::InvalidParams(format!("invalid typed data: {err}"))
+}
+
+fn invalid_value(r#type: &str, value: &Value) -> EthProviderError {
+    EthProviderError::InvalidParams(format!("invalid typed data value {value} for type {}", r#type))
+}
+
+fn unknown_type(r#type: &str) -> EthProviderError {
+    EthProviderError::InvalidParams(format!("unknown typed data type {}", r#type))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::Keystore;
+    use serde_json::json;
+
+    /// Example of <https://eips.ethereum.org/EIPS/eip-712>, see
+    /// <https://github.com/ethereum/EIPs/blob/master/assets/eip-712/Example.js>
+    fn mail() -> Value {
+        json!({
+            "types": {
+                "EIP712Domain": [
+                    {"name": "name", "type": "string"},
+                    {"name": "version", "type": "string"},
+                    {"name": "chainId", "type": "uint256"},
+                    {"name": "verifyingContract", "type": "address"}
+                ],
+                "Person": [
+                    {"name": "name", "type": "string"},
+                    {"name": "wallet", "type": "address"}
+                ],
+                "Mail": [
+                    {"name": "from", "type": "Person"},
+                    {"name": "to", "type": "Person"},
+                    {"name": "contents", "type": "string"}
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                "contents": "Hello, Bob!"
+            }
+        })
+    }
+
+    #[test]
+    fn test_encode_type() {
+        // Given
+        let typed_data = TypedData::from_value(mail()).unwrap();
+
+        // When
+        let encoded = typed_data.encode_type("Mail").unwrap();
+        let type_hash = typed_data.type_hash("Mail").unwrap();
+
+        // Then
+        assert_eq!(encoded, "Mail(Person from,Person to,string contents)Person(string name,address wallet)");
+        assert_eq!(
+            type_hash,
+            B256::from_str("0xa0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_hash_typed_data() {
+        // Given
+        let typed_data = TypedData::from_value(mail()).unwrap();
+
+        // When
+        let domain_separator = typed_data.domain_separator().unwrap();
+        let message_hash = typed_data.hash_struct("Mail", &typed_data.message).unwrap();
+        let hash = typed_data.hash().unwrap();
+
+        // Then
+        assert_eq!(
+            domain_separator,
+            B256::from_str("0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f").unwrap()
+        );
+        assert_eq!(
+            message_hash,
+            B256::from_str("0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e").unwrap()
+        );
+        assert_eq!(hash, B256::from_str("0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2").unwrap());
+    }
+
+    #[test]
+    fn test_implicit_domain_type() {
+        // Given
+        let mut data = mail();
+        data["types"].as_object_mut().unwrap().remove(EIP712_DOMAIN);
+
+        // When
+        let typed_data = TypedData::from_value(data).unwrap();
+
+        // Then
+        assert_eq!(
+            typed_data.domain_separator().unwrap(),
+            B256::from_str("0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_typed_data_as_string() {
+        // Given
+        let data = Value::String(mail().to_string());
+
+        // When
+        let typed_data = TypedData::from_value(data).unwrap();
+
+        // Then
+        assert_eq!(typed_data.primary_type, "Mail");
+    }
+
+    #[test]
+    fn test_sign_typed_data() {
+        // Given
+        let private_key = keccak256("cow");
+        let keystore = Keystore::from_private_keys([private_key]).unwrap();
+        let address = Address::from_str("0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826").unwrap();
+        let typed_data = TypedData::from_value(mail()).unwrap();
+
+        // When
+        let signature = keystore.sign_typed_data(address, &typed_data).unwrap();
+
+        // Then
+        assert_eq!(
+            &signature[..32],
+            B256::from_str("0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d").unwrap().as_slice()
+        );
+        assert_eq!(
+            &signature[32..64],
+            B256::from_str("0x07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562").unwrap().as_slice()
+        );
+        assert_eq!(signature[64], 28);
+    }
+
+    #[test]
+    fn test_encode_atomic_values() {
+        // Given
+        let typed_data = TypedData::from_value(mail()).unwrap();
+
+        // When
+        let negative = typed_data.encode_value("int8", &json!(-1)).unwrap();
+        let hex = typed_data.encode_value("uint256", &json!("0x10")).unwrap();
+        let fixed_bytes = typed_data.encode_value("bytes2", &json!("0x0102")).unwrap();
+        let overflow = typed_data.encode_value("uint8", &json!(256));
+        let unknown = typed_data.encode_value("Unknown", &json!(1));
+
+        // Then
+        assert_eq!(negative, B256::repeat_byte(0xff));
+        assert_eq!(hex, B256::from(U256::from(16)));
+        assert_eq!(fixed_bytes, B256::right_padding_from(&[1, 2]));
+        assert!(overflow.is_err());
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_encode_array() {
+        // Given
+        let typed_data = TypedData::from_value(mail()).unwrap();
+
+        // When
+        let array = typed_data.encode_value("uint256[]", &json!([1, 2])).unwrap();
+
+        // Then
+        let mut expected = B256::from(U256::from(1)).to_vec();
+        expected.extend_from_slice(B256::from(U256::from(2)).as_slice());
+        assert_eq!(array, keccak256(expected));
+    }
+}
@@ -0,0 +1,443 @@
+pub mod eip712;
+pub mod remote;
+
+use std::fmt;
+use std::path::PathBuf;
+
+use ethers::signers::{LocalWallet, Signer};
+use eyre::Result;
+use reth_primitives::{
+    keccak256, sign_message, AccessList, AccessListItem, Address, BlockNumberOrTag, Bytes, Signature, Transaction,
+    TransactionKind, TransactionSigned, TxEip1559, TxLegacy, B256, U256, U64,
+};
+use reth_rpc_types::TransactionRequest;
+
+use url::Url;
+
+use crate::accounts::eip712::TypedData;
+use crate::accounts::remote::RemoteSigner;
+use crate::eth_provider::error::EthProviderError;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
+
+/// Configuration of the accounts managed by the RPC.
+/// Accounts are either loaded from raw private keys and encrypted JSON keystore files,
+/// or held by a remote signer, in which case the RPC only knows their addresses.
+#[derive(Clone, Default)]
+pub struct AccountsConfig {
+    pub private_keys: Vec<B256>,
+    pub keystore_files: Vec<PathBuf>,
+    pub keystore_password: Option<String>,
+    pub remote_signer_url: Option<Url>,
+    pub remote_signer_allowed_addresses: Vec<Address>,
+}
+
+impl fmt::Debug for AccountsConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountsConfig")
+            .field("private_keys", &format_args!("[{} redacted]", self.private_keys.len()))
+            .field("keystore_files", &self.keystore_files)
+            .field("keystore_password", &self.keystore_password.as_ref().map(|_| "redacted"))
+            .field("remote_signer_url", &self.remote_signer_url)
+            .field("remote_signer_allowed_addresses", &self.remote_signer_allowed_addresses)
+            .finish()
+    }
+}
+
+impl AccountsConfig {
+    /// Returns the signer of the configured accounts, or `None` if no account is configured.
+    pub fn signer(&self) -> Result<Option<AccountSigner>> {
+        let keystore = self.keystore()?;
+        match (&self.remote_signer_url, keystore) {
+            (Some(_), Some(_)) => {
+                Err(eyre::eyre!("Local accounts and a remote signer can't be configured at the same time"))
+            }
+            (Some(url), None) => {
+                if self.remote_signer_allowed_addresses.is_empty() {
+                    return Err(eyre::eyre!("Missing the allowed addresses of the remote signer"));
+                }
+                let signer = RemoteSigner::new(url.clone(), self.remote_signer_allowed_addresses.clone());
+                Ok(Some(AccountSigner::Remote(signer)))
+            }
+            (None, keystore) => Ok(keystore.map(AccountSigner::Keystore)),
+        }
+    }
+
+    /// Returns the keystore holding the configured accounts, or `None` if no account is configured.
+    pub fn keystore(&self) -> Result<Option<Keystore>> {
+        if self.private_keys.is_empty() && self.keystore_files.is_empty() {
+            return Ok(None);
+        }
+
+        let mut keystore = Keystore::from_private_keys(self.private_keys.iter().copied())?;
+        if !self.keystore_files.is_empty() {
+            let password = self
+                .keystore_password
+                .as_deref()
+                .ok_or_else(|| eyre::eyre!("Missing the password decrypting the keystore files"))?;
+            for path in &self.keystore_files {
+                let wallet = LocalWallet::decrypt_keystore(path, password)
+                    .map_err(|err| eyre::eyre!("failed to decrypt keystore {}: {err}", path.display()))?;
+                keystore.insert(B256::from_slice(wallet.signer().to_bytes().as_slice()))?;
+            }
+        }
+
+        Ok(Some(keystore))
+    }
+}
+
+/// Signer of the accounts managed by the RPC.
+#[derive(Debug, Clone)]
+pub enum AccountSigner {
+    /// Accounts whose private keys are held by the RPC.
+    Keystore(Keystore),
+    /// Accounts whose private keys are held by a remote signer.
+    Remote(RemoteSigner),
+}
+
+impl AccountSigner {
+    pub fn addresses(&self) -> Vec<Address> {
+        match self {
+            Self::Keystore(keystore) => keystore.addresses(),
+            Self::Remote(signer) => signer.addresses().to_vec(),
+        }
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        match self {
+            Self::Keystore(keystore) => keystore.contains(address),
+            Self::Remote(signer) => signer.addresses().contains(&address),
+        }
+    }
+
+    /// Signs the filled transaction request, see [`fill_transaction_request`].
+    pub async fn sign_transaction(&self, request: TransactionRequest) -> EthProviderResult<TransactionSigned> {
+        let (from, transaction) = into_transaction(request.clone())?;
+        if !self.contains(from) {
+            return Err(EthProviderError::UnknownAccount(from));
+        }
+
+        match self {
+            Self::Keystore(keystore) => keystore.sign_transaction(from, transaction),
+            Self::Remote(signer) => signer.sign_transaction(from, &request, transaction).await,
+        }
+    }
+}
+
+impl From<Keystore> for AccountSigner {
+    fn from(keystore: Keystore) -> Self {
+        Self::Keystore(keystore)
+    }
+}
+
+/// Holds the private keys of the accounts managed by the RPC and signs on their behalf.
+#[derive(Clone, Default)]
+pub struct Keystore {
+    accounts: Vec<(Address, B256)>,
+}
+
+impl fmt::Debug for Keystore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keystore").field("addresses", &self.addresses()).finish_non_exhaustive()
+    }
+}
+
+impl Keystore {
+    pub fn from_private_keys(private_keys: impl IntoIterator<Item = B256>) -> Result<Self> {
+        let mut keystore = Self::default();
+        for private_key in private_keys {
+            keystore.insert(private_key)?;
+        }
+        Ok(keystore)
+    }
+
+    /// Adds the account of the private key to the keystore, ignoring duplicates.
+    pub fn insert(&mut self, private_key: B256) -> Result<Address> {
+        let wallet = LocalWallet::from_bytes(private_key.as_slice())?;
+        let address = Address::from_slice(wallet.address().as_bytes());
+        if !self.contains(address) {
+            self.accounts.push((address, private_key));
+        }
+        Ok(address)
+    }
+
+    /// Returns the managed addresses, in insertion order.
+    pub fn addresses(&self) -> Vec<Address> {
+        self.accounts.iter().map(|(address, _)| *address).collect()
+    }
+
+    pub fn contains(&self, address: Address) -> bool {
+        self.accounts.iter().any(|(a, _)| *a == address)
+    }
+
+    fn private_key(&self, address: Address) -> EthProviderResult<B256> {
+        self.accounts
+            .iter()
+            .find_map(|(a, private_key)| (*a == address).then_some(*private_key))
+            .ok_or(EthProviderError::UnknownAccount(address))
+    }
+
+    /// Signs the hash with the private key of the address.
+    pub fn sign_hash(&self, address: Address, hash: B256) -> EthProviderResult<Signature> {
+        let private_key = self.private_key(address)?;
+        Ok(sign_message(private_key, hash).map_err(|err| eyre::eyre!("failed to sign: {err}"))?)
+    }
+
+    /// Signs the message prefixed with `"\x19Ethereum Signed Message:\n" + len(message)`, see EIP-191.
+    pub fn sign_message(&self, address: Address, message: &[u8]) -> EthProviderResult<Bytes> {
+        let signature = self.sign_hash(address, hash_message(message))?;
+        Ok(signature_to_bytes(&signature))
+    }
+
+    /// Signs the typed data following EIP-712.
+    pub fn sign_typed_data(&self, address: Address, typed_data: &TypedData) -> EthProviderResult<Bytes> {
+        let signature = self.sign_hash(address, typed_data.hash()?)?;
+        Ok(signature_to_bytes(&signature))
+    }
+
+    pub fn sign_transaction(&self, address: Address, transaction: Transaction) -> EthProviderResult<TransactionSigned> {
+        let signature = self.sign_hash(address, transaction.signature_hash())?;
+        Ok(TransactionSigned::from_transaction_and_signature(transaction, signature))
+    }
+}
+
+/// Hashes the message following EIP-191 (version 0x45).
+pub fn hash_message(message: &[u8]) -> B256 {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    keccak256(prefixed)
+}
+
+/// Encodes the signature as `r || s || v`, with `v` in {27, 28}.
+pub fn signature_to_bytes(signature: &Signature) -> Bytes {
+    let mut bytes = Vec::with_capacity(65);
+    bytes.extend_from_slice(&signature.r.to_be_bytes::<32>());
+    bytes.extend_from_slice(&signature.s.to_be_bytes::<32>());
+    bytes.push(27 + u8::from(signature.odd_y_parity));
+    bytes.into()
+}
+
+/// Fills the missing nonce, gas limit, fees and chain id of the request from the provider.
+/// Fees are left untouched for legacy requests, i.e. requests setting a gas price.
+pub async fn fill_transaction_request<P: EthereumProvider>(
+    provider: &P,
+    mut request: TransactionRequest,
+) -> EthProviderResult<TransactionRequest> {
+    let from = request.from.ok_or_else(|| EthProviderError::InvalidParams("missing from address".to_string()))?;
+
+    if request.chain_id.is_none() {
+        request.chain_id = Some(provider.chain_id().await?.unwrap_or_default());
+    }
+    if request.nonce.is_none() {
+        request.nonce = Some(U64::from(provider.transaction_count(from, None).await?.to::<u64>()));
+    }
+    if request.gas.is_none() {
+        request.gas = Some(provider.estimate_gas(request.clone(), None).await?);
+    }
+    if request.gas_price.is_some() {
+        return Ok(request);
+    }
+
+    if request.max_priority_fee_per_gas.is_none() {
+        request.max_priority_fee_per_gas = Some(provider.max_priority_fee_per_gas().await?);
+    }
+    if request.max_fee_per_gas.is_none() {
+        // Leave room for the base fee of the latest block to double, as most wallets do.
+        let latest = provider
+            .block_by_number(BlockNumberOrTag::Latest, false)
+            .await?
+            .ok_or_else(|| EthProviderError::ValueNotFound("Block".to_string()))?;
+        let base_fee = latest.header.base_fee_per_gas.unwrap_or_default();
+        let max_priority_fee_per_gas = request.max_priority_fee_per_gas.unwrap_or_default();
+        request.max_fee_per_gas = Some(base_fee.saturating_mul(U256::from(2)).saturating_add(max_priority_fee_per_gas));
+    }
+
+    Ok(request)
+}
+
+/// Converts the filled request into a transaction, along with its sender.
+/// A legacy transaction is built if the request sets a gas price, an EIP-1559 transaction otherwise.
+pub fn into_transaction(request: TransactionRequest) -> EthProviderResult<(Address, Transaction)> {
+    let missing = |field: &str| EthProviderError::InvalidParams(format!("missing {field}"));
+
+    let from = request.from.ok_or_else(|| missing("from address"))?;
+    let chain_id = request.chain_id.ok_or_else(|| missing("chain id"))?.to::<u64>();
+    let nonce = request.nonce.ok_or_else(|| missing("nonce"))?.to::<u64>();
+    let gas_limit = request.gas.ok_or_else(|| missing("gas"))?;
+    let gas_limit =
+        u64::try_from(gas_limit).map_err(|_| EthProviderError::InvalidParams("gas too high".to_string()))?;
+
+    let to = request.to.map_or(TransactionKind::Create, TransactionKind::Call);
+    let value = request.value.unwrap_or_default();
+    let input = request.input.into_input().unwrap_or_default();
+
+    if let Some(gas_price) = request.gas_price {
+        return Ok((
+            from,
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(chain_id),
+                nonce,
+                gas_price: to_u128(gas_price)?,
+                gas_limit,
+                to,
+                value: value.into(),
+                input,
+            }),
+        ));
+    }
+
+    let max_fee_per_gas = request.max_fee_per_gas.ok_or_else(|| missing("max fee per gas"))?;
+    let max_priority_fee_per_gas =
+        request.max_priority_fee_per_gas.ok_or_else(|| missing("max priority fee per gas"))?;
+    let access_list = request.access_list.map(|access_list| {
+        AccessList(
+            access_list
+                .0
+                .into_iter()
+                .map(|item| AccessListItem { address: item.address, storage_keys: item.storage_keys })
+                .collect(),
+        )
+    });
+
+    Ok((
+        from,
+        Transaction::Eip1559(TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit,
+            max_fee_per_gas: to_u128(max_fee_per_gas)?,
+            max_priority_fee_per_gas: to_u128(max_priority_fee_per_gas)?,
+            to,
+            value: value.into(),
+            input,
+            access_list: access_list.unwrap_or_default(),
+        }),
+    ))
+}
+
+fn to_u128(value: U256) -> EthProviderResult<u128> {
+    u128::try_from(value).map_err(|_| EthProviderError::InvalidParams(format!("fee too high: {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::core::rand::thread_rng;
+    use std::str::FromStr;
+
+    // Anvil first account
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+    #[test]
+    fn test_keystore_addresses() {
+        // Given
+        let private_key = B256::from_str(PRIVATE_KEY).unwrap();
+
+        // When
+        let keystore = Keystore::from_private_keys([private_key, private_key]).unwrap();
+
+        // Then
+        assert_eq!(keystore.addresses(), vec![Address::from_str(ADDRESS).unwrap()]);
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        // Given
+        let private_key = B256::from_str(PRIVATE_KEY).unwrap();
+        let config = AccountsConfig {
+            private_keys: vec![private_key],
+            keystore_password: Some("password".to_string()),
+            ..Default::default()
+        };
+        let keystore = config.keystore().unwrap().unwrap();
+
+        // When
+        let debug = format!("{config:?} {keystore:?} {:?}", AccountSigner::from(keystore.clone()));
+
+        // Then
+        assert!(!debug.contains(&PRIVATE_KEY[2..]));
+        assert!(!debug.contains("password\""));
+        assert!(debug.contains("[1 redacted]"));
+        assert!(debug.contains(&format!("{:?}", Address::from_str(ADDRESS).unwrap())));
+    }
+
+    #[test]
+    fn test_sign_message() {
+        // Given
+        let address = Address::from_str(ADDRESS).unwrap();
+        let keystore = Keystore::from_private_keys([B256::from_str(PRIVATE_KEY).unwrap()]).unwrap();
+
+        // When
+        let signature = keystore.sign_hash(address, hash_message(b"hello")).unwrap();
+        let bytes = keystore.sign_message(address, b"hello").unwrap();
+
+        // Then
+        assert_eq!(signature.recover_signer(hash_message(b"hello")), Some(address));
+        assert_eq!(bytes, signature_to_bytes(&signature));
+        assert_eq!(bytes.len(), 65);
+        assert!(bytes[64] == 27 || bytes[64] == 28);
+    }
+
+    #[test]
+    fn test_sign_unknown_account() {
+        // Given
+        let keystore = Keystore::from_private_keys([B256::from_str(PRIVATE_KEY).unwrap()]).unwrap();
+
+        // When
+        let result = keystore.sign_message(Address::ZERO, b"hello");
+
+        // Then
+        assert!(matches!(result, Err(EthProviderError::UnknownAccount(_))));
+    }
+
+    #[test]
+    fn test_keystore_from_encrypted_file() {
+        // Given
+        let dir = std::env::temp_dir();
+        let name = format!("kakarot-rpc-keystore-{}", std::process::id());
+        let private_key = B256::from_str(PRIVATE_KEY).unwrap();
+        LocalWallet::encrypt_keystore(&dir, &mut thread_rng(), private_key.as_slice(), "password", Some(&name))
+            .unwrap();
+        let config = AccountsConfig {
+            keystore_files: vec![dir.join(&name)],
+            keystore_password: Some("password".to_string()),
+            ..Default::default()
+        };
+
+        // When
+        let keystore = config.keystore().unwrap().unwrap();
+        std::fs::remove_file(dir.join(&name)).unwrap();
+
+        // Then
+        assert_eq!(keystore.addresses(), vec![Address::from_str(ADDRESS).unwrap()]);
+    }
+
+    #[test]
+    fn test_empty_config_has_no_keystore() {
+        assert!(AccountsConfig::default().keystore().unwrap().is_none());
+        assert!(AccountsConfig::default().signer().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_remote_signer_config() {
+        // Given
+        let url = Some(Url::parse("http://localhost:9000").unwrap());
+        let address = Address::from_str(ADDRESS).unwrap();
+        let remote = AccountsConfig {
+            remote_signer_url: url.clone(),
+            remote_signer_allowed_addresses: vec![address],
+            ..Default::default()
+        };
+        let no_allowed_address = AccountsConfig { remote_signer_url: url.clone(), ..Default::default() };
+        let both = AccountsConfig { private_keys: vec![B256::from_str(PRIVATE_KEY).unwrap()], ..remote.clone() };
+
+        // When
+        let signer = remote.signer().unwrap().unwrap();
+
+        // Then
+        assert!(matches!(signer, AccountSigner::Remote(_)));
+        assert_eq!(signer.addresses(), vec![address]);
+        assert!(no_allowed_address.signer().is_err());
+        assert!(both.signer().is_err());
+    }
+}
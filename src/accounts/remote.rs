@@ -0,0 +1,188 @@
+use alloy_rlp::Decodable as _;
+use reth_primitives::{Address, Bytes, Transaction, TransactionSigned};
+use reth_rpc_types::TransactionRequest;
+use serde_json::{json, Map, Value};
+use url::Url;
+
+use crate::eth_provider::error::EthProviderError;
+use crate::eth_provider::provider::EthProviderResult;
+
+/// Signer forwarding the signature of transactions to a web3signer compatible endpoint,
+/// see <https://docs.web3signer.consensys.io/reference/api/json-rpc#eth_signtransaction>.
+/// Only the configured addresses can be used as senders.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: Url,
+    allowed_addresses: Vec<Address>,
+}
+
+impl RemoteSigner {
+    pub fn new(url: Url, allowed_addresses: Vec<Address>) -> Self {
+        Self { client: reqwest::Client::new(), url, allowed_addresses }
+    }
+
+    pub fn addresses(&self) -> &[Address] {
+        &self.allowed_addresses
+    }
+
+    /// Forwards the filled request to the remote signer. The returned transaction is
+    /// checked to be the expected one, signed by the sender of the request.
+    pub async fn sign_transaction(
+        &self,
+        from: Address,
+        request: &TransactionRequest,
+        expected: Transaction,
+    ) -> EthProviderResult<TransactionSigned> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_signTransaction",
+            "params": [to_signer_transaction(from, request)],
+        });
+
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(eyre::Error::from)?
+            .text()
+            .await
+            .map_err(eyre::Error::from)?;
+        let mut response: Value = serde_json::from_str(&response).map_err(eyre::Error::from)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(EthProviderError::Other(eyre::eyre!("remote signer failed: {error}")));
+        }
+        let result = response.get_mut("result").map(Value::take).unwrap_or_default();
+        let bytes: Bytes = serde_json::from_value(result).map_err(eyre::Error::from)?;
+
+        let transaction = TransactionSigned::decode(&mut bytes.as_ref())
+            .map_err(|err| eyre::eyre!("remote signer returned an invalid transaction: {err}"))?;
+        if transaction.recover_signer() != Some(from) {
+            return Err(EthProviderError::Other(eyre::eyre!("remote signer signed with another account than {from}")));
+        }
+        if transaction.transaction != expected {
+            return Err(EthProviderError::Other(eyre::eyre!("remote signer signed another transaction")));
+        }
+
+        Ok(transaction)
+    }
+}
+
+/// Converts the filled request into the transaction object taken by web3signer.
+fn to_signer_transaction(from: Address, request: &TransactionRequest) -> Value {
+    let mut transaction = Map::new();
+    transaction.insert("from".to_string(), json!(from));
+    if let Some(to) = request.to {
+        transaction.insert("to".to_string(), json!(to));
+    }
+    let fields = [
+        ("gas", request.gas.map(|gas| json!(gas))),
+        ("gasPrice", request.gas_price.map(|fee| json!(fee))),
+        ("maxFeePerGas", request.max_fee_per_gas.map(|fee| json!(fee))),
+        ("maxPriorityFeePerGas", request.max_priority_fee_per_gas.map(|fee| json!(fee))),
+        ("value", request.value.map(|value| json!(value))),
+        ("nonce", request.nonce.map(|nonce| json!(nonce))),
+        ("chainId", request.chain_id.map(|chain_id| json!(chain_id))),
+        ("data", request.input.input().map(|data| json!(data))),
+        ("accessList", request.access_list.as_ref().map(|access_list| json!(access_list))),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            transaction.insert(name.to_string(), value);
+        }
+    }
+    Value::Object(transaction)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use jsonrpsee::server::{ServerBuilder, ServerHandle};
+    use jsonrpsee::types::ErrorObjectOwned;
+    use jsonrpsee::RpcModule;
+    use reth_primitives::{B256, U256, U64};
+
+    use super::*;
+    use crate::accounts::{into_transaction, Keystore};
+
+    // Anvil first and second accounts
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+    const OTHER_PRIVATE_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    /// Starts a web3signer mock, signing all the transactions with the first account of the keystore.
+    async fn start_mock_signer(keystore: Keystore) -> (Url, ServerHandle) {
+        let server = ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+        let addr = server.local_addr().unwrap();
+
+        let mut module = RpcModule::new(keystore);
+        module
+            .register_method("eth_signTransaction", |params, keystore| -> Result<Bytes, ErrorObjectOwned> {
+                let request: TransactionRequest = params.one()?;
+                let (_, transaction) = into_transaction(request)?;
+                let transaction = keystore.sign_transaction(keystore.addresses()[0], transaction)?;
+                let mut bytes = Vec::new();
+                transaction.encode_enveloped(&mut bytes);
+                Ok(bytes.into())
+            })
+            .unwrap();
+
+        (Url::parse(&format!("http://{addr}")).unwrap(), server.start(module))
+    }
+
+    fn request(from: Address) -> TransactionRequest {
+        TransactionRequest {
+            from: Some(from),
+            to: Some(Address::with_last_byte(1)),
+            value: Some(U256::from(1)),
+            gas: Some(U256::from(21_000)),
+            max_fee_per_gas: Some(U256::from(2)),
+            max_priority_fee_per_gas: Some(U256::from(1)),
+            nonce: Some(U64::from(3)),
+            chain_id: Some(U64::from(1_263_227_476)),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_sign_transaction() {
+        // Given
+        let keystore = Keystore::from_private_keys([B256::from_str(PRIVATE_KEY).unwrap()]).unwrap();
+        let from = keystore.addresses()[0];
+        let (url, handle) = start_mock_signer(keystore).await;
+        let signer = RemoteSigner::new(url, vec![from]);
+
+        // When
+        let request = request(from);
+        let (_, expected) = into_transaction(request.clone()).unwrap();
+        let transaction = signer.sign_transaction(from, &request, expected.clone()).await.unwrap();
+
+        // Then
+        assert_eq!(transaction.recover_signer(), Some(from));
+        assert_eq!(transaction.transaction, expected);
+        handle.stop().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_remote_sign_transaction_wrong_signer() {
+        // Given
+        let keystore = Keystore::from_private_keys([B256::from_str(OTHER_PRIVATE_KEY).unwrap()]).unwrap();
+        let from = Keystore::from_private_keys([B256::from_str(PRIVATE_KEY).unwrap()]).unwrap().addresses()[0];
+        let (url, handle) = start_mock_signer(keystore).await;
+        let signer = RemoteSigner::new(url, vec![from]);
+
+        // When
+        let request = request(from);
+        let (_, expected) = into_transaction(request.clone()).unwrap();
+        let result = signer.sign_transaction(from, &request, expected).await;
+
+        // Then
+        assert!(result.is_err());
+        handle.stop().unwrap();
+    }
+}
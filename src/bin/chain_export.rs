@@ -0,0 +1,405 @@
+use std::collections::HashMap;
+use std::io::Write as _;
+use std::path::PathBuf;
+
+use alloy_rlp::Encodable;
+use eyre::eyre;
+use kakarot_rpc::eth_provider::database::types::{
+    header::StoredHeader, receipt::StoredTransactionReceipt, transaction::StoredTransaction,
+};
+use kakarot_rpc::eth_provider::database::Database;
+use kakarot_rpc::models::consensus::{
+    into_consensus_header, into_consensus_receipt, into_signed_transaction, link_header,
+};
+use mongodb::bson::{doc, Document};
+use mongodb::options::{DatabaseOptions, ReadConcern};
+use reth_primitives::{Block, Header, ReceiptWithBloom, TransactionSigned, B256, U256};
+use reth_rpc_types::{Transaction, TransactionReceipt};
+use sha2::{Digest, Sha256};
+use tokio::{
+    fs::File,
+    io::{AsyncWrite, AsyncWriteExt},
+};
+
+/// Number of blocks fetched from the database at once.
+const BATCH_SIZE: u64 = 1_000;
+
+/// Maximum number of blocks in an Era1 archive.
+const MAX_ERA1_SIZE: u64 = 8_192;
+
+// e2store entry types, see <https://github.com/ethereum/go-ethereum/blob/master/internal/era/era.go>
+const VERSION: u16 = 0x3265;
+const COMPRESSED_HEADER: u16 = 0x03;
+const COMPRESSED_BODY: u16 = 0x04;
+const COMPRESSED_RECEIPTS: u16 = 0x05;
+const TOTAL_DIFFICULTY: u16 = 0x06;
+const ACCUMULATOR: u16 = 0x07;
+const BLOCK_INDEX: u16 = 0x3266;
+
+/// Exports the indexed blocks into a RLP chain file, importable by reth and geth and
+/// readable by the `hive_chain` binary. Optionally writes the blocks into Era1 archives.
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+    let db_client = mongodb::Client::with_uri_str(std::env::var("MONGO_CONNECTION_STRING")?).await?;
+    let db = Database::new(db_client.database_with_options(
+        &std::env::var("MONGO_DATABASE_NAME")?,
+        DatabaseOptions::builder().read_concern(ReadConcern::MAJORITY).build(),
+    ));
+
+    let latest: Option<StoredHeader> = db.get_one("headers", doc! {}, doc! {"header.number": -1}).await?;
+    let latest = latest
+        .and_then(|header| header.header.number)
+        .ok_or_else(|| eyre!("No block indexed in the database"))?
+        .to::<u64>();
+    let from = env_block_number("EXPORT_FROM_BLOCK")?.unwrap_or_default();
+    let to = env_block_number("EXPORT_TO_BLOCK")?.unwrap_or(latest);
+    if from > to || to > latest {
+        return Err(eyre!("Invalid block range {from}..={to}, the latest indexed block is {latest}"));
+    }
+
+    let chain_path = PathBuf::from(std::env::var("CHAIN_PATH")?);
+    let chain_file = File::create(&chain_path).await?;
+    let era1 = match std::env::var("ERA1_DIR") {
+        Ok(dir) => {
+            tokio::fs::create_dir_all(&dir).await?;
+            let network = std::env::var("ERA1_NETWORK").unwrap_or_else(|_| "kakarot".to_string());
+            Some(Era1Writer::new(PathBuf::from(dir), network))
+        }
+        Err(_) => None,
+    };
+    let mut exporter = Exporter::new(chain_file, era1);
+
+    // The headers are linked from the genesis, the ones preceding the range are only hashed.
+    for start in (0..from).step_by(BATCH_SIZE as usize) {
+        let end = (from - 1).min(start + BATCH_SIZE - 1);
+        for header in fetch_headers(&db, start, end).await? {
+            exporter.skip(header);
+        }
+    }
+    for start in (from..=to).step_by(BATCH_SIZE as usize) {
+        let end = to.min(start + BATCH_SIZE - 1);
+        for (block, receipts) in fetch_blocks(&db, start, end).await? {
+            exporter.push(block, &receipts).await?;
+        }
+        println!("Exported blocks {start} to {end}");
+    }
+    exporter.finish().await?;
+    println!("Chain written to {}", chain_path.display());
+
+    Ok(())
+}
+
+/// Reads an optional block number from the environment.
+fn env_block_number(name: &str) -> eyre::Result<Option<u64>> {
+    std::env::var(name).ok().map(|value| value.parse().map_err(|err| eyre!("Invalid {name}: {err}"))).transpose()
+}
+
+/// Filter matching the documents of the block range on the given field.
+fn range_filter(field: &str, start: u64, end: u64) -> Document {
+    doc! {field: {"$gte": format!("0x{start:064x}"), "$lte": format!("0x{end:064x}")}}
+}
+
+/// Fetches the consensus headers of the range, sorted by number.
+async fn fetch_headers(db: &Database, start: u64, end: u64) -> eyre::Result<Vec<Header>> {
+    let headers: Vec<StoredHeader> = db.get("headers", range_filter("header.number", start, end), None).await?;
+    let mut headers =
+        headers.into_iter().map(|header| into_consensus_header(header.header)).collect::<Result<Vec<_>, _>>()?;
+    headers.sort_by_key(|header| header.number);
+    headers.dedup_by_key(|header| header.number);
+    if headers.len() as u64 != end - start + 1 {
+        return Err(eyre!("Missing headers between blocks {start} and {end}"));
+    }
+    Ok(headers)
+}
+
+/// Fetches the blocks of the range, along with the receipts of their transactions.
+async fn fetch_blocks(db: &Database, start: u64, end: u64) -> eyre::Result<Vec<(Block, Vec<ReceiptWithBloom>)>> {
+    let headers = fetch_headers(db, start, end).await?;
+    let transactions: Vec<StoredTransaction> =
+        db.get("transactions", range_filter("tx.blockNumber", start, end), None).await?;
+    let receipts: Vec<StoredTransactionReceipt> =
+        db.get("receipts", range_filter("receipt.blockNumber", start, end), None).await?;
+
+    let mut block_transactions: HashMap<u64, Vec<Transaction>> = HashMap::new();
+    for transaction in transactions.into_iter().map(Transaction::from) {
+        let number = transaction.block_number.ok_or_else(|| eyre!("Missing block number of {}", transaction.hash))?;
+        block_transactions.entry(number.to::<u64>()).or_default().push(transaction);
+    }
+    let mut block_receipts: HashMap<u64, Vec<TransactionReceipt>> = HashMap::new();
+    for receipt in receipts.into_iter().map(TransactionReceipt::from) {
+        let number = receipt
+            .block_number
+            .ok_or_else(|| eyre!("Missing block number of receipt {:?}", receipt.transaction_hash))?;
+        block_receipts.entry(number.to::<u64>()).or_default().push(receipt);
+    }
+
+    headers
+        .into_iter()
+        .map(|header| {
+            let mut transactions = block_transactions.remove(&header.number).unwrap_or_default();
+            transactions.sort_by_key(|transaction| transaction.transaction_index);
+            let mut receipts = block_receipts.remove(&header.number).unwrap_or_default();
+            receipts.sort_by_key(|receipt| receipt.transaction_index);
+            if transactions.len() != receipts.len() {
+                return Err(eyre!("Missing transactions or receipts in block {}", header.number));
+            }
+
+            let body = transactions.into_iter().map(into_signed_transaction).collect::<Result<Vec<_>, _>>()?;
+            let receipts = receipts.into_iter().map(into_consensus_receipt).collect::<Result<Vec<_>, _>>()?;
+            let withdrawals = header.withdrawals_root.map(|_| Vec::new());
+
+            Ok((Block { header, body, ommers: Vec::new(), withdrawals }, receipts))
+        })
+        .collect()
+}
+
+/// Writes the blocks into the chain file and the Era1 archives. The indexed parent hashes are
+/// Starknet block hashes, so each header is linked to the hash of the previous exported header.
+struct Exporter<W> {
+    chain: W,
+    era1: Option<Era1Writer>,
+    parent_hash: Option<B256>,
+    // Kakarot blocks have no difficulty, the total difficulty is kept for the Era1 archives.
+    total_difficulty: U256,
+}
+
+impl<W: AsyncWrite + Unpin> Exporter<W> {
+    const fn new(chain: W, era1: Option<Era1Writer>) -> Self {
+        Self { chain, era1, parent_hash: None, total_difficulty: U256::ZERO }
+    }
+
+    /// Links the header to the previous one, without exporting it.
+    fn skip(&mut self, mut header: Header) {
+        self.parent_hash = Some(link_header(&mut header, self.parent_hash));
+        self.total_difficulty += header.difficulty;
+    }
+
+    async fn push(&mut self, mut block: Block, receipts: &[ReceiptWithBloom]) -> eyre::Result<()> {
+        let hash = link_header(&mut block.header, self.parent_hash);
+        self.parent_hash = Some(hash);
+        self.total_difficulty += block.header.difficulty;
+
+        let mut buf = Vec::new();
+        block.encode(&mut buf);
+        self.chain.write_all(&buf).await?;
+        if let Some(era1) = self.era1.as_mut() {
+            era1.push(&block, hash, receipts, self.total_difficulty).await?;
+        }
+        Ok(())
+    }
+
+    async fn finish(&mut self) -> eyre::Result<()> {
+        self.chain.flush().await?;
+        if let Some(era1) = self.era1.as_mut() {
+            era1.finish().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes the blocks into Era1 archives of [`MAX_ERA1_SIZE`] blocks, one per epoch,
+/// see <https://github.com/eth-clients/e2store-format-specs/blob/main/formats/era1.md>.
+struct Era1Writer {
+    dir: PathBuf,
+    network: String,
+    epoch: u64,
+    start: u64,
+    buffer: Vec<u8>,
+    offsets: Vec<u64>,
+    records: Vec<(B256, U256)>,
+}
+
+impl Era1Writer {
+    fn new(dir: PathBuf, network: String) -> Self {
+        Self { dir, network, epoch: 0, start: 0, buffer: Vec::new(), offsets: Vec::new(), records: Vec::new() }
+    }
+
+    async fn push(
+        &mut self,
+        block: &Block,
+        hash: B256,
+        receipts: &[ReceiptWithBloom],
+        total_difficulty: U256,
+    ) -> eyre::Result<()> {
+        let epoch = block.header.number / MAX_ERA1_SIZE;
+        if self.records.is_empty() || epoch != self.epoch {
+            self.finish().await?;
+            self.epoch = epoch;
+            self.start = block.header.number;
+            write_entry(&mut self.buffer, VERSION, &[]);
+        }
+
+        self.offsets.push(self.buffer.len() as u64);
+        write_entry(&mut self.buffer, COMPRESSED_HEADER, &snappy(&alloy_rlp::encode(&block.header))?);
+        write_entry(&mut self.buffer, COMPRESSED_BODY, &snappy(&encode_body(&block.body, &block.ommers))?);
+        let mut encoded_receipts = Vec::new();
+        alloy_rlp::encode_list::<ReceiptWithBloom, ReceiptWithBloom>(receipts, &mut encoded_receipts);
+        write_entry(&mut self.buffer, COMPRESSED_RECEIPTS, &snappy(&encoded_receipts)?);
+        write_entry(&mut self.buffer, TOTAL_DIFFICULTY, &total_difficulty.to_le_bytes::<32>());
+
+        self.records.push((hash, total_difficulty));
+        Ok(())
+    }
+
+    /// Writes the accumulator and the block index of the current epoch, and flushes the archive.
+    async fn finish(&mut self) -> eyre::Result<()> {
+        if self.records.is_empty() {
+            return Ok(());
+        }
+
+        let root = accumulator_root(&self.records);
+        write_entry(&mut self.buffer, ACCUMULATOR, root.as_slice());
+
+        // The offsets are relative to the start of the block index entry.
+        let index_offset = self.buffer.len() as i64;
+        let mut index = Vec::with_capacity(16 + 8 * self.offsets.len());
+        index.extend_from_slice(&self.start.to_le_bytes());
+        for offset in &self.offsets {
+            index.extend_from_slice(&(*offset as i64 - index_offset).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.offsets.len() as u64).to_le_bytes());
+        write_entry(&mut self.buffer, BLOCK_INDEX, &index);
+
+        let path = self.dir.join(format!("{}-{:05}-{}.era1", self.network, self.epoch, hex::encode(&root[..4])));
+        tokio::fs::write(&path, &self.buffer).await?;
+        println!("Era1 archive written to {}", path.display());
+
+        self.buffer.clear();
+        self.offsets.clear();
+        self.records.clear();
+        Ok(())
+    }
+}
+
+/// Appends an e2store entry: a little endian type and length, 2 reserved bytes and the data.
+fn write_entry(buffer: &mut Vec<u8>, entry_type: u16, data: &[u8]) {
+    buffer.extend_from_slice(&entry_type.to_le_bytes());
+    buffer.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&[0, 0]);
+    buffer.extend_from_slice(data);
+}
+
+/// Compresses the data using the snappy framed format.
+fn snappy(data: &[u8]) -> eyre::Result<Vec<u8>> {
+    let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+    encoder.write_all(data)?;
+    encoder.into_inner().map_err(|err| eyre!("Failed to compress data: {}", err.error()))
+}
+
+/// Encodes the body of a block, as the list of its transactions and ommers.
+fn encode_body(transactions: &[TransactionSigned], ommers: &[Header]) -> Vec<u8> {
+    let payload_length = alloy_rlp::list_length::<TransactionSigned, TransactionSigned>(transactions)
+        + alloy_rlp::list_length::<Header, Header>(ommers);
+    let mut out = Vec::new();
+    alloy_rlp::Header { list: true, payload_length }.encode(&mut out);
+    alloy_rlp::encode_list::<TransactionSigned, TransactionSigned>(transactions, &mut out);
+    alloy_rlp::encode_list::<Header, Header>(ommers, &mut out);
+    out
+}
+
+/// Computes the SSZ hash tree root of the list of header records (block hash and total difficulty).
+fn accumulator_root(records: &[(B256, U256)]) -> B256 {
+    let mut layer: Vec<[u8; 32]> = records
+        .iter()
+        .map(|(hash, total_difficulty)| sha256(hash.as_slice(), &total_difficulty.to_le_bytes::<32>()))
+        .collect();
+    layer.resize(MAX_ERA1_SIZE as usize, [0; 32]);
+    while layer.len() > 1 {
+        layer = layer.chunks(2).map(|pair| sha256(&pair[0], &pair[1])).collect();
+    }
+
+    // Mix in the length of the list.
+    let mut length = [0; 32];
+    length[..8].copy_from_slice(&(records.len() as u64).to_le_bytes());
+    B256::from(sha256(&layer[0], &length))
+}
+
+fn sha256(left: &[u8], right: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read as _;
+
+    use alloy_rlp::Decodable;
+
+    use super::*;
+
+    /// Returns an empty block, whose parent hash is a Starknet block hash.
+    fn block(number: u64) -> Block {
+        let header =
+            Header { number, parent_hash: B256::with_last_byte(0xff), gas_limit: 1_000_000, ..Default::default() };
+        Block { header, body: Vec::new(), ommers: Vec::new(), withdrawals: None }
+    }
+
+    /// Returns the offset, type and data of the e2store entries.
+    fn read_entries(archive: &[u8]) -> Vec<(usize, u16, &[u8])> {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        while offset < archive.len() {
+            let entry_type = u16::from_le_bytes(archive[offset..offset + 2].try_into().unwrap());
+            let length = u32::from_le_bytes(archive[offset + 2..offset + 6].try_into().unwrap()) as usize;
+            entries.push((offset, entry_type, &archive[offset + 8..offset + 8 + length]));
+            offset += 8 + length;
+        }
+        entries
+    }
+
+    #[tokio::test]
+    async fn test_export() {
+        // Given
+        let dir = std::env::temp_dir().join(format!("kakarot-chain-export-{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let mut exporter = Exporter::new(Vec::new(), Some(Era1Writer::new(dir.clone(), "test".to_string())));
+
+        // When
+        exporter.skip(block(0).header);
+        for number in 1..=3 {
+            exporter.push(block(number), &[]).await.unwrap();
+        }
+        exporter.finish().await.unwrap();
+
+        // Then
+        // The chain file decodes into headers linked by their hashes, starting from the skipped genesis
+        let mut chain = exporter.chain.as_slice();
+        let mut headers = Vec::new();
+        while !chain.is_empty() {
+            headers.push(Block::decode(&mut chain).unwrap().header);
+        }
+        assert_eq!(headers.iter().map(|header| header.number).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(headers[0].parent_hash, block(0).header.hash_slow());
+        for pair in headers.windows(2) {
+            assert_eq!(pair[1].parent_hash, pair[0].hash_slow());
+        }
+
+        // The accumulator of the archive is computed from the linked hashes
+        let mut paths = std::fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect::<Vec<_>>();
+        assert_eq!(paths.len(), 1);
+        let archive = std::fs::read(paths.remove(0)).unwrap();
+        let entries = read_entries(&archive);
+        let records = headers.iter().map(|header| (header.hash_slow(), U256::ZERO)).collect::<Vec<_>>();
+        let accumulator = entries.iter().find(|(_, entry_type, _)| *entry_type == ACCUMULATOR).unwrap();
+        assert_eq!(accumulator.2, accumulator_root(&records).as_slice());
+
+        // The block index points to the compressed headers
+        let (index_offset, entry_type, index) = entries.last().copied().unwrap();
+        assert_eq!(entry_type, BLOCK_INDEX);
+        assert_eq!(u64::from_le_bytes(index[..8].try_into().unwrap()), 1);
+        assert_eq!(u64::from_le_bytes(index[index.len() - 8..].try_into().unwrap()), 3);
+        for (i, header) in headers.iter().enumerate() {
+            let offset = i64::from_le_bytes(index[8 + 8 * i..16 + 8 * i].try_into().unwrap());
+            let position = (index_offset as i64 + offset) as usize;
+            let (_, entry_type, data) = entries.iter().find(|(offset, _, _)| *offset == position).copied().unwrap();
+            assert_eq!(entry_type, COMPRESSED_HEADER);
+            let mut encoded = Vec::new();
+            snap::read::FrameDecoder::new(data).read_to_end(&mut encoded).unwrap();
+            assert_eq!(&Header::decode(&mut encoded.as_slice()).unwrap(), header);
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,13 +1,17 @@
 use alloy_rlp::Decodable;
+use dotenv::dotenv;
 use eyre::eyre;
-use kakarot_rpc::eth_provider::starknet::kakarot_core::to_starknet_transaction;
+use kakarot_rpc::config::KakarotRpcConfig;
+use kakarot_rpc::eth_provider::starknet::kakarot_core::{starknet_address, to_starknet_transaction};
+use kakarot_rpc::test_utils::tx_waiter::watch_tx;
 use reth_primitives::{bytes::Buf, Block, BlockBody, BytesMut};
 use starknet::{
-    core::types::BroadcastedInvokeTransaction,
-    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider},
+    core::types::{BlockId, BlockTag, BroadcastedInvokeTransaction, StarknetError},
+    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider, ProviderError},
 };
 use starknet_crypto::FieldElement;
-use std::{path::Path, str::FromStr};
+use std::collections::{hash_map::Entry, HashMap};
+use std::{path::Path, str::FromStr, time::Duration};
 use tokio::{fs::File, io::AsyncReadExt};
 use tokio_stream::StreamExt;
 use tokio_util::codec::{Decoder, FramedRead};
@@ -34,6 +38,7 @@ impl Decoder for BlockFileCodec {
 /// https://github.com/paradigmxyz/reth/blob/main/bin/reth/src/commands/import.rs
 #[tokio::main]
 async fn main() -> eyre::Result<()> {
+    dotenv().ok();
     let chain_path = Path::new(&std::env::var("CHAIN_PATH")?).to_path_buf();
     let mut file = File::open(chain_path).await?;
 
@@ -53,29 +58,65 @@ async fn main() -> eyre::Result<()> {
     }
 
     let provider = JsonRpcClient::new(HttpTransport::new(Url::from_str(&std::env::var("STARKNET_NETWORK")?)?));
-    let mut current_nonce = FieldElement::ZERO;
+    let kakarot = KakarotRpcConfig::from_env().map(|config| config.kakarot_deployment())?;
+    println!("Importing the chain from Starknet block {}", provider.block_number().await?);
 
-    for (block_number, body) in bodies.into_iter().enumerate() {
-        while provider.block_number().await? < block_number as u64 {
-            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-        }
+    // Transactions already included on Starknet, i.e. whose nonce is below the current nonce
+    // of their sender, are skipped, which allows resuming an interrupted import.
+    let mut nonces = HashMap::new();
 
+    for (block_number, body) in bodies.into_iter().enumerate() {
         // TODO(HIVE): Set the block gas limit in kakarot
 
+        let mut block_started = false;
         for transaction in body.transactions {
             let signer = transaction.recover_signer().ok_or(eyre!("Failed to recover signer"))?;
             let chain_id = transaction.chain_id().ok_or(eyre!("Failed to recover chain id"))?;
-            let starknet_tx = to_starknet_transaction(&transaction, chain_id, signer, u64::MAX)?;
-
-            // Stop if the nonce is incorrect
-            assert_eq!(starknet_tx.nonce, current_nonce);
-
-            provider.add_invoke_transaction(BroadcastedInvokeTransaction::V1(starknet_tx)).await?;
-
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            current_nonce += 1u8.into();
+            let starknet_tx = to_starknet_transaction(&kakarot, &transaction, chain_id, signer, u64::MAX)?;
+
+            let nonce = match nonces.entry(signer) {
+                Entry::Occupied(entry) => entry.into_mut(),
+                Entry::Vacant(entry) => {
+                    entry.insert(current_nonce(&provider, starknet_address(&kakarot, signer)).await?)
+                }
+            };
+            if starknet_tx.nonce < *nonce {
+                continue;
+            }
+            if starknet_tx.nonce > *nonce {
+                return Err(eyre!(
+                    "Invalid nonce for transaction {} from {signer}: expected {}, got {}",
+                    transaction.hash,
+                    nonce,
+                    starknet_tx.nonce
+                ));
+            }
+
+            if !block_started {
+                while provider.block_number().await? < block_number as u64 {
+                    tokio::time::sleep(Duration::from_millis(500)).await;
+                }
+                block_started = true;
+            }
+
+            let transaction_hash =
+                provider.add_invoke_transaction(BroadcastedInvokeTransaction::V1(starknet_tx)).await?.transaction_hash;
+            watch_tx(&provider, transaction_hash, Duration::from_millis(100), 100)
+                .await
+                .map_err(|err| eyre!("Transaction {} failed: {err}", transaction.hash))?;
+
+            *nonce += FieldElement::ONE;
         }
     }
 
     Ok(())
 }
+
+/// Returns the pending nonce of the account, or zero if the account is not deployed yet.
+async fn current_nonce<P: Provider>(provider: &P, address: FieldElement) -> eyre::Result<FieldElement> {
+    match provider.get_nonce(BlockId::Tag(BlockTag::Pending), address).await {
+        Ok(nonce) => Ok(nonce),
+        Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Ok(FieldElement::ZERO),
+        Err(err) => Err(err.into()),
+    }
+}
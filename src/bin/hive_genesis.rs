@@ -11,6 +11,10 @@ fn main() {
         Path::new(&var("KAKAROT_CONTRACTS_PATH").expect("Failed to load KAKAROT_CONTRACTS_PATH var")).to_path_buf();
     let hive_genesis_path =
         Path::new(&var("HIVE_GENESIS_PATH").expect("Failed to load HIVE_GENESIS_PATH var")).to_path_buf();
+    let chain_id = var("KATANA_CHAIN_ID")
+        .expect("Failed to load KATANA_CHAIN_ID var")
+        .parse()
+        .expect("Failed to parse KATANA_CHAIN_ID var");
 
     // Read all the classes.
     let mut builder = KatanaGenesisBuilder::default().load_classes(kakarot_contracts_path);
@@ -24,8 +28,9 @@ fn main() {
         serde_json::from_str(&hive_genesis_content).expect("Failed to parse hive genesis json");
 
     // Convert the hive genesis to a katana genesis.
-    let genesis_json =
-        hive_genesis.try_into_genesis_json(builder.clone()).expect("Failed to convert hive genesis to katana genesis");
+    let genesis_json = hive_genesis
+        .try_into_genesis_json(builder.clone(), chain_id)
+        .expect("Failed to convert hive genesis to katana genesis");
 
     let builder = builder.with_kakarot(FieldElement::ZERO).expect("Failed to set up Kakarot");
     let manifest = builder.manifest();
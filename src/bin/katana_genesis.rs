@@ -1,14 +1,22 @@
 use dotenv::dotenv;
-use ethers::types::U256;
-use kakarot_rpc::test_utils::katana::genesis::KatanaGenesisBuilder;
+use ethers::signers::{LocalWallet, Signer};
+use ethers::types::U256 as EthersU256;
+use kakarot_rpc::test_utils::evm_contract::{EvmContract, KakarotEvmContract};
+use kakarot_rpc::test_utils::fixtures::{genesis_contracts, GENESIS_DEPLOYER};
+use kakarot_rpc::test_utils::hive::{AccountInfo, HiveGenesisConfig};
+use kakarot_rpc::test_utils::katana::genesis::{create_foundry_artifact, CreatedContract, KatanaGenesisBuilder};
+use kakarot_rpc::test_utils::katana::predeploys::{Predeploy, PREDEPLOYS};
 use lazy_static::lazy_static;
-use reth_primitives::B256;
+use reth_primitives::{Address, B256, U256, U64};
+use starknet::core::utils::cairo_short_string_to_felt;
 use starknet_crypto::FieldElement;
 use std::{
+    collections::HashMap,
     env::var,
     path::{Path, PathBuf},
     str::FromStr,
 };
+use url::Url;
 
 lazy_static! {
     static ref GENESIS_FOLDER_PATH: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR")).to_path_buf().join(".katana");
@@ -18,7 +26,11 @@ lazy_static! {
     static ref SALT: FieldElement = FieldElement::ZERO;
 }
 
-fn main() {
+/// Chain id Katana is started with by `make run-katana`, which Kakarot reports as its own.
+const KATANA_CHAIN_ID: &str = "kkrt";
+
+#[tokio::main]
+async fn main() {
     // Load the env vars.
     dotenv().ok();
 
@@ -27,16 +39,80 @@ fn main() {
         .expect("Failed to parse EVM private key");
 
     // Read all the classes.
-    let mut builder = KatanaGenesisBuilder::default()
-        .load_classes(KAKAROT_CONTRACTS_PATH.clone())
-        .with_kakarot(*COINBASE_ADDRESS)
-        .expect("Failed to set up Kakarot");
-    builder = builder.with_eoa(pk).expect("Failed to set up EOA").fund(pk, U256::from(u128::MAX)).unwrap();
-    builder = builder.with_dev_allocation(10);
-
+    let loaded = KatanaGenesisBuilder::default().load_classes(KAKAROT_CONTRACTS_PATH.clone());
+    let builder = loaded.clone().with_kakarot(*COINBASE_ADDRESS).expect("Failed to set up Kakarot");
     let manifest = builder.manifest();
 
-    let genesis = builder.build().expect("Failed to build genesis");
+    // Read the canonical contracts to predeploy, as a comma separated list of names or "all".
+    let predeploys = match var("GENESIS_PREDEPLOYS").unwrap_or_default().as_str() {
+        "" => vec![],
+        "all" => PREDEPLOYS.iter().collect(),
+        names => names.split(',').map(|name| Predeploy::by_name(name.trim()).expect("Invalid predeploy")).collect(),
+    };
+    let reference_rpc = var("PREDEPLOYS_REFERENCE_RPC_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+        .map(|url| Url::parse(&url).expect("Failed to parse PREDEPLOYS_REFERENCE_RPC_URL"));
+    let mut predeploys_code = Vec::with_capacity(predeploys.len());
+    for predeploy in predeploys {
+        let code = predeploy.code(reference_rpc.as_ref()).await.expect("Failed to get predeploy code");
+        predeploys_code.push((predeploy.address, code));
+    }
+
+    // Add the contracts used by the test fixtures if requested, i.e. for the tests.
+    let test_contracts = match var("GENESIS_TEST_CONTRACTS").unwrap_or_default().as_str() {
+        "" | "false" => vec![],
+        "true" => fixture_contracts(),
+        value => panic!("Invalid GENESIS_TEST_CONTRACTS {value}, expected true or false"),
+    };
+
+    let genesis = match var("GETH_GENESIS_PATH").ok().filter(|path| !path.is_empty()) {
+        // Convert the geth genesis, funding the EOA of the private key if it isn't allocated.
+        Some(path) => {
+            let content = std::fs::read_to_string(path).expect("Failed to read geth genesis file");
+            let mut geth_genesis: HiveGenesisConfig =
+                serde_json::from_str(&content).expect("Failed to parse geth genesis json");
+            let evm_address =
+                LocalWallet::from_bytes(pk.as_slice()).expect("Failed to parse EVM private key").address();
+            geth_genesis
+                .alloc
+                .entry(Address::from_slice(evm_address.as_bytes()))
+                .or_insert_with(|| AccountInfo { balance: U256::from(u128::MAX), ..Default::default() });
+            for (address, code) in predeploys_code {
+                geth_genesis.alloc.entry(address).or_insert_with(|| AccountInfo {
+                    code: Some(code),
+                    nonce: Some(U64::from(1)),
+                    ..Default::default()
+                });
+            }
+            for contract in test_contracts {
+                geth_genesis.alloc.entry(contract.address).or_insert_with(|| AccountInfo {
+                    balance: contract.balance,
+                    code: Some(contract.code),
+                    storage: Some(contract.storage.into_iter().collect()),
+                    nonce: Some(U64::from(contract.nonce)),
+                });
+            }
+
+            let chain_id = cairo_short_string_to_felt(KATANA_CHAIN_ID)
+                .ok()
+                .and_then(|chain_id| u64::try_from(chain_id).ok())
+                .expect("Failed to convert the Katana chain id");
+            geth_genesis
+                .try_into_genesis_json(loaded.with_dev_allocation(10), chain_id)
+                .expect("Failed to convert geth genesis to katana genesis")
+        }
+        None => {
+            let mut builder =
+                builder.with_eoa(pk).expect("Failed to set up EOA").fund(pk, EthersU256::from(u128::MAX)).unwrap();
+            for (address, code) in predeploys_code {
+                builder =
+                    builder.with_evm_contract(address, code, HashMap::new()).expect("Failed to predeploy contract");
+            }
+            builder = builder.with_created_contracts(test_contracts).expect("Failed to add test contracts");
+            builder.with_dev_allocation(10).build().expect("Failed to build genesis")
+        }
+    };
 
     // Write the genesis json to the file.
     std::fs::create_dir_all(GENESIS_FOLDER_PATH.as_path()).expect("Failed to create genesis directory");
@@ -50,3 +126,18 @@ fn main() {
     std::fs::write(manifest_path, serde_json::to_string(&manifest).expect("Failed to serialize manifest json"))
         .expect("Failed to write manifest json");
 }
+
+/// Returns the contracts used by the test fixtures, created by the [`GENESIS_DEPLOYER`], see
+/// [`genesis_contracts`].
+fn fixture_contracts() -> Vec<CreatedContract> {
+    genesis_contracts()
+        .into_iter()
+        .enumerate()
+        .flat_map(|(nonce, (name, constructor_args))| {
+            let artifact = <KakarotEvmContract as EvmContract>::load_contract_bytecode(name)
+                .expect("Failed to load contract artifact, run `make setup`");
+            create_foundry_artifact(&artifact, constructor_args.as_slice(), *GENESIS_DEPLOYER, nonce as u64)
+                .expect("Failed to deploy genesis contract")
+        })
+        .collect()
+}
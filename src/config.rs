@@ -3,12 +3,16 @@ use starknet::core::types::FieldElement;
 use starknet::providers::jsonrpc::{HttpTransport, JsonRpcTransport};
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
 use std::env::var;
+use std::str::FromStr;
 use url::Url;
 
-fn env_var_to_field_element(var_name: &str) -> Result<FieldElement, eyre::Error> {
-    let env_var = var(var_name)?;
+use crate::eth_provider::starknet::kakarot_core::KakarotDeployment;
 
-    Ok(FieldElement::from_hex_be(&env_var)?)
+#[cfg(any(feature = "hive", feature = "testing"))]
+pub(crate) fn env_var_to_field_element(var_name: &str) -> Result<FieldElement, eyre::Error> {
+    let env_var = var(var_name).map_err(|_| eyre!("Missing environment variable {var_name}"))?;
+
+    FieldElement::from_hex_be(&env_var).map_err(|_| eyre!("Invalid hex string for {var_name}"))
 }
 
 #[derive(Default, Clone, Debug)]
@@ -46,6 +50,25 @@ impl Network {
     }
 }
 
+impl FromStr for Network {
+    type Err = eyre::Error;
+
+    /// Parses the network from its name. Non-standard providers (i.e. not "katana", "madara",
+    /// "mainnet"...) are parsed from the URL of their JsonRpc endpoint.
+    fn from_str(network: &str) -> Result<Self, Self::Err> {
+        Ok(match network.to_lowercase().as_str() {
+            "katana" => Self::Katana,
+            "madara" => Self::Madara,
+            "sharingan" => Self::Sharingan,
+            "mainnet" => Self::MainnetGateway,
+            "goerli1" => Self::Goerli1Gateway,
+            "goerli2" => Self::Goerli2Gateway,
+            "testnet" => Self::Goerli1Gateway,
+            _ => Self::JsonRpcProvider(Url::parse(network)?),
+        })
+    }
+}
+
 #[derive(Default, Clone)]
 /// Configuration for the Starknet RPC client.
 pub struct KakarotRpcConfig {
@@ -78,22 +101,26 @@ impl KakarotRpcConfig {
         }
     }
 
+    /// Returns the Kakarot deployment of the configuration.
+    pub const fn kakarot_deployment(&self) -> KakarotDeployment {
+        KakarotDeployment::new(
+            self.kakarot_address,
+            self.proxy_account_class_hash,
+            self.externally_owned_account_class_hash,
+            self.contract_account_class_hash,
+        )
+    }
+
     /// Create a new `StarknetConfig` from environment variables.
     /// When using non-standard providers (i.e. not "katana", "madara", "mainnet"), the
     /// `STARKNET_NETWORK` environment variable should be set the URL of a JsonRpc
     /// starknet provider, e.g. https://starknet-goerli.g.alchemy.com/v2/some_key.
+    /// Only used by the hive tooling, the RPC reads its configuration from the [`crate::cli::Settings`].
+    #[cfg(any(feature = "hive", feature = "testing"))]
     pub fn from_env() -> Result<Self, eyre::Error> {
-        let network = var("STARKNET_NETWORK")?;
-        let network = match network.to_lowercase().as_str() {
-            "katana" => Network::Katana,
-            "madara" => Network::Madara,
-            "sharingan" => Network::Sharingan,
-            "mainnet" => Network::MainnetGateway,
-            "goerli1" => Network::Goerli1Gateway,
-            "goerli2" => Network::Goerli2Gateway,
-            "testnet" => Network::Goerli1Gateway,
-            network_url => Network::JsonRpcProvider(Url::parse(network_url)?),
-        };
+        let network = Network::from_str(
+            &var("STARKNET_NETWORK").map_err(|_| eyre!("Missing environment variable STARKNET_NETWORK"))?,
+        )?;
 
         let kakarot_address = env_var_to_field_element("KAKAROT_ADDRESS")?;
         let proxy_account_class_hash = env_var_to_field_element("PROXY_ACCOUNT_CLASS_HASH")?;
@@ -0,0 +1,437 @@
+use std::future::Future;
+use std::hash::Hash;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use lru::LruCache;
+use prometheus::IntCounterVec;
+use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, U256, U64};
+use reth_rpc_types::{
+    EIP1186AccountProofResponse, FeeHistory, Filter, FilterChanges, Index, JsonStorageKey, RichBlock, SyncStatus,
+    Transaction, TransactionReceipt, TransactionRequest, U64HexOrNumber,
+};
+
+use super::provider::{EthProviderResult, EthereumProvider};
+use crate::metrics::{CACHE_HITS, CACHE_MISSES};
+use crate::models::health::ProviderHealth;
+
+/// Default number of blocks kept in the cache.
+pub const DEFAULT_CACHED_BLOCKS: usize = 1_000;
+
+/// Default number of transactions, and of receipts, kept in the cache.
+pub const DEFAULT_CACHED_TRANSACTIONS: usize = 10_000;
+
+/// Default number of blocks below the indexed head from which the blocks are final.
+pub const DEFAULT_FINALITY_DEPTH: u64 = 10;
+
+/// Default duration for which the short-lived values, e.g. the base fee, are cached.
+pub const DEFAULT_VALUES_TTL: Duration = Duration::from_secs(2);
+
+/// Sizes of the caches of the [`CachingProvider`], a size of 0 disabling the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// Number of blocks, and of block receipts, kept in the cache.
+    pub blocks: usize,
+    /// Number of transactions, and of transaction receipts, kept in the cache.
+    pub transactions: usize,
+    /// Number of blocks below the indexed head from which the blocks are final.
+    pub finality_depth: u64,
+    /// Duration for which the short-lived values, e.g. the base fee, are cached.
+    pub values_ttl: Duration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            blocks: DEFAULT_CACHED_BLOCKS,
+            transactions: DEFAULT_CACHED_TRANSACTIONS,
+            finality_depth: DEFAULT_FINALITY_DEPTH,
+            values_ttl: DEFAULT_VALUES_TTL,
+        }
+    }
+}
+
+/// Key of the blocks in the caches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum BlockKey {
+    Hash(B256),
+    Number(u64),
+}
+
+/// Bounded LRU cache, recording its hits and misses in the metrics.
+struct Cache<K: Hash + Eq, V> {
+    name: &'static str,
+    entries: Option<Mutex<LruCache<K, Arc<V>>>>,
+}
+
+impl<K: Hash + Eq, V> Cache<K, V> {
+    fn new(name: &'static str, size: usize) -> Self {
+        Self { name, entries: NonZeroUsize::new(size).map(|size| Mutex::new(LruCache::new(size))) }
+    }
+
+    fn get(&self, key: &K) -> Option<Arc<V>> {
+        let entries = self.entries.as_ref()?;
+        let value = entries.lock().unwrap_or_else(PoisonError::into_inner).get(key).cloned();
+        let counter: &IntCounterVec = if value.is_some() { &CACHE_HITS } else { &CACHE_MISSES };
+        counter.with_label_values(&[self.name]).inc();
+        value
+    }
+
+    fn insert(&self, keys: impl IntoIterator<Item = K>, value: V) {
+        let Some(entries) = &self.entries else {
+            return;
+        };
+        let value = Arc::new(value);
+        let mut entries = entries.lock().unwrap_or_else(PoisonError::into_inner);
+        for key in keys {
+            entries.put(key, value.clone());
+        }
+    }
+}
+
+/// Value cached for a limited duration, a duration of 0 disabling the cache.
+#[derive(Debug)]
+pub struct TtlCell<T> {
+    ttl: Duration,
+    value: Mutex<Option<(Instant, T)>>,
+}
+
+impl<T: Clone> TtlCell<T> {
+    pub const fn new(ttl: Duration) -> Self {
+        Self { ttl, value: Mutex::new(None) }
+    }
+
+    /// Returns the cached value if it didn't expire, else the value returned by `f`, which is
+    /// cached if successful.
+    pub async fn get_or_try_insert_with<F, Fut, E>(&self, f: F) -> Result<T, E>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        if let Some(value) = self.get(Instant::now()) {
+            return Ok(value);
+        }
+        let value = f().await?;
+        if !self.ttl.is_zero() {
+            *self.value.lock().unwrap_or_else(PoisonError::into_inner) = Some((Instant::now(), value.clone()));
+        }
+        Ok(value)
+    }
+
+    fn get(&self, now: Instant) -> Option<T> {
+        let value = self.value.lock().unwrap_or_else(PoisonError::into_inner);
+        value
+            .as_ref()
+            .filter(|(cached_at, _)| now.saturating_duration_since(*cached_at) < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Ethereum provider caching the blocks, transactions and receipts which are final, i.e. at or
+/// below the finalized block, and delegating everything else to the inner provider.
+///
+/// The finalized block is [`CacheConfig::finality_depth`] blocks below the indexed head, a depth
+/// heuristic rather than the `ACCEPTED_ON_L1` status of the Starknet blocks, so a block reverted
+/// deeper than the depth stays cached until evicted. The head is only queried when the data
+/// fetched is above the last known finalized block.
+pub struct CachingProvider<P> {
+    inner: P,
+    finality_depth: u64,
+    finalized: AtomicU64,
+    blocks: Cache<(BlockKey, bool), RichBlock>,
+    block_receipts: Cache<BlockKey, Vec<TransactionReceipt>>,
+    transactions: Cache<B256, Transaction>,
+    receipts: Cache<B256, TransactionReceipt>,
+}
+
+impl<P> CachingProvider<P>
+where
+    P: EthereumProvider + Send + Sync,
+{
+    pub fn new(inner: P, config: CacheConfig) -> Self {
+        Self {
+            inner,
+            finality_depth: config.finality_depth,
+            finalized: AtomicU64::new(0),
+            blocks: Cache::new("blocks", config.blocks),
+            block_receipts: Cache::new("block_receipts", config.blocks),
+            transactions: Cache::new("transactions", config.transactions),
+            receipts: Cache::new("receipts", config.transactions),
+        }
+    }
+
+    /// Returns whether the block is at or below the finalized block.
+    async fn is_finalized(&self, block_number: Option<U256>) -> bool {
+        let Some(block_number) = block_number.and_then(|number| u64::try_from(number).ok()) else {
+            return false;
+        };
+        if block_number <= self.finalized.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let Ok(head) = self.inner.block_number().await else {
+            return false;
+        };
+        let finalized = head.to::<u64>().saturating_sub(self.finality_depth);
+        self.finalized.fetch_max(finalized, Ordering::Relaxed);
+        block_number <= finalized
+    }
+
+    /// Caches the block under its hash and number if it is final.
+    async fn cache_block(&self, block: &Option<RichBlock>, full: bool) {
+        let Some(block) = block else {
+            return;
+        };
+        if !self.is_finalized(block.header.number).await {
+            return;
+        }
+        let number = block.header.number.and_then(|number| u64::try_from(number).ok());
+        let keys = block.header.hash.map(BlockKey::Hash).into_iter().chain(number.map(BlockKey::Number));
+        self.blocks.insert(keys.map(|key| (key, full)), block.clone());
+    }
+}
+
+/// Returns the cache key of the block number, if the number isn't a tag.
+const fn block_number_key(number_or_tag: BlockNumberOrTag) -> Option<BlockKey> {
+    match number_or_tag {
+        BlockNumberOrTag::Number(number) => Some(BlockKey::Number(number)),
+        BlockNumberOrTag::Earliest => Some(BlockKey::Number(0)),
+        _ => None,
+    }
+}
+
+#[async_trait]
+impl<P> EthereumProvider for CachingProvider<P>
+where
+    P: EthereumProvider + Send + Sync,
+{
+    async fn block_number(&self) -> EthProviderResult<U64> {
+        self.inner.block_number().await
+    }
+
+    async fn syncing(&self) -> EthProviderResult<SyncStatus> {
+        self.inner.syncing().await
+    }
+
+    async fn chain_id(&self) -> EthProviderResult<Option<U64>> {
+        self.inner.chain_id().await
+    }
+
+    async fn coinbase(&self) -> EthProviderResult<Address> {
+        self.inner.coinbase().await
+    }
+
+    async fn block_by_hash(&self, hash: B256, full: bool) -> EthProviderResult<Option<RichBlock>> {
+        if let Some(block) = self.blocks.get(&(BlockKey::Hash(hash), full)) {
+            return Ok(Some(RichBlock::clone(&block)));
+        }
+        let block = self.inner.block_by_hash(hash, full).await?;
+        self.cache_block(&block, full).await;
+        Ok(block)
+    }
+
+    async fn block_by_number(
+        &self,
+        number_or_tag: BlockNumberOrTag,
+        full: bool,
+    ) -> EthProviderResult<Option<RichBlock>> {
+        let Some(key) = block_number_key(number_or_tag) else {
+            return self.inner.block_by_number(number_or_tag, full).await;
+        };
+        if let Some(block) = self.blocks.get(&(key, full)) {
+            return Ok(Some(RichBlock::clone(&block)));
+        }
+        let block = self.inner.block_by_number(number_or_tag, full).await?;
+        self.cache_block(&block, full).await;
+        Ok(block)
+    }
+
+    async fn block_transaction_count_by_hash(&self, hash: B256) -> EthProviderResult<Option<U256>> {
+        self.inner.block_transaction_count_by_hash(hash).await
+    }
+
+    async fn block_transaction_count_by_number(
+        &self,
+        number_or_tag: BlockNumberOrTag,
+    ) -> EthProviderResult<Option<U256>> {
+        self.inner.block_transaction_count_by_number(number_or_tag).await
+    }
+
+    async fn transaction_by_hash(&self, hash: B256) -> EthProviderResult<Option<Transaction>> {
+        if let Some(transaction) = self.transactions.get(&hash) {
+            return Ok(Some(Transaction::clone(&transaction)));
+        }
+        let transaction = self.inner.transaction_by_hash(hash).await?;
+        if let Some(transaction) = &transaction {
+            if self.is_finalized(transaction.block_number).await {
+                self.transactions.insert([hash], transaction.clone());
+            }
+        }
+        Ok(transaction)
+    }
+
+    async fn transaction_by_block_hash_and_index(
+        &self,
+        hash: B256,
+        index: Index,
+    ) -> EthProviderResult<Option<Transaction>> {
+        self.inner.transaction_by_block_hash_and_index(hash, index).await
+    }
+
+    async fn transaction_by_block_number_and_index(
+        &self,
+        number_or_tag: BlockNumberOrTag,
+        index: Index,
+    ) -> EthProviderResult<Option<Transaction>> {
+        self.inner.transaction_by_block_number_and_index(number_or_tag, index).await
+    }
+
+    async fn transaction_receipt(&self, hash: B256) -> EthProviderResult<Option<TransactionReceipt>> {
+        if let Some(receipt) = self.receipts.get(&hash) {
+            return Ok(Some(TransactionReceipt::clone(&receipt)));
+        }
+        let receipt = self.inner.transaction_receipt(hash).await?;
+        if let Some(receipt) = &receipt {
+            if self.is_finalized(receipt.block_number).await {
+                self.receipts.insert([hash], receipt.clone());
+            }
+        }
+        Ok(receipt)
+    }
+
+    async fn balance(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256> {
+        self.inner.balance(address, block_id).await
+    }
+
+    async fn storage_at(
+        &self,
+        address: Address,
+        index: JsonStorageKey,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<B256> {
+        self.inner.storage_at(address, index, block_id).await
+    }
+
+    async fn transaction_count(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256> {
+        self.inner.transaction_count(address, block_id).await
+    }
+
+    async fn get_code(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<Bytes> {
+        self.inner.get_code(address, block_id).await
+    }
+
+    async fn get_logs(&self, filter: Filter) -> EthProviderResult<FilterChanges> {
+        self.inner.get_logs(filter).await
+    }
+
+    async fn call(&self, request: TransactionRequest, block_id: Option<BlockId>) -> EthProviderResult<Bytes> {
+        self.inner.call(request, block_id).await
+    }
+
+    async fn estimate_gas(&self, call: TransactionRequest, block_id: Option<BlockId>) -> EthProviderResult<U256> {
+        self.inner.estimate_gas(call, block_id).await
+    }
+
+    async fn fee_history(
+        &self,
+        block_count: U64HexOrNumber,
+        newest_block: BlockNumberOrTag,
+        reward_percentiles: Option<Vec<f64>>,
+    ) -> EthProviderResult<FeeHistory> {
+        self.inner.fee_history(block_count, newest_block, reward_percentiles).await
+    }
+
+    async fn send_raw_transaction(&self, transaction: Bytes) -> EthProviderResult<B256> {
+        self.inner.send_raw_transaction(transaction).await
+    }
+
+    async fn gas_price(&self) -> EthProviderResult<U256> {
+        self.inner.gas_price().await
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> EthProviderResult<U256> {
+        self.inner.max_priority_fee_per_gas().await
+    }
+
+    async fn block_receipts(&self, block_id: Option<BlockId>) -> EthProviderResult<Option<Vec<TransactionReceipt>>> {
+        let key = match block_id {
+            Some(BlockId::Hash(hash)) => Some(BlockKey::Hash(hash.block_hash)),
+            Some(BlockId::Number(number_or_tag)) => block_number_key(number_or_tag),
+            None => None,
+        };
+        let Some(key) = key else {
+            return self.inner.block_receipts(block_id).await;
+        };
+        if let Some(receipts) = self.block_receipts.get(&key) {
+            return Ok(Some(Vec::clone(&receipts)));
+        }
+
+        let receipts = self.inner.block_receipts(block_id).await?;
+        if let Some(receipts) = &receipts {
+            // The block of the receipts is known from the key, or from the receipts themselves
+            let number = match key {
+                BlockKey::Number(number) => Some(U256::from(number)),
+                BlockKey::Hash(_) => receipts.first().and_then(|receipt| receipt.block_number),
+            };
+            if self.is_finalized(number).await {
+                let hash = receipts.first().and_then(|receipt| receipt.block_hash).map(BlockKey::Hash);
+                let number = number.and_then(|number| u64::try_from(number).ok()).map(BlockKey::Number);
+                self.block_receipts.insert([Some(key), hash, number].into_iter().flatten(), receipts.clone());
+            }
+        }
+        Ok(receipts)
+    }
+
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<B256>,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<EIP1186AccountProofResponse> {
+        self.inner.get_proof(address, keys, block_id).await
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        self.inner.health().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ttl_cell() {
+        // Given
+        let cell = TtlCell::new(Duration::from_millis(50));
+        let fetches = &AtomicU64::new(0);
+        let fetch = move || async move { Ok::<_, Infallible>(fetches.fetch_add(1, Ordering::Relaxed)) };
+
+        // When
+        let first = cell.get_or_try_insert_with(fetch).await.unwrap();
+        let cached = cell.get_or_try_insert_with(fetch).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let expired = cell.get_or_try_insert_with(fetch).await.unwrap();
+
+        // Then
+        assert_eq!((first, cached, expired), (0, 0, 1));
+    }
+
+    #[tokio::test]
+    async fn test_ttl_cell_disabled() {
+        // Given
+        let cell = TtlCell::new(Duration::ZERO);
+
+        // When
+        let first = cell.get_or_try_insert_with(|| async { Ok::<_, Infallible>(1) }).await.unwrap();
+        let second = cell.get_or_try_insert_with(|| async { Ok::<_, Infallible>(2) }).await.unwrap();
+
+        // Then
+        assert_eq!((first, second), (1, 2));
+    }
+}
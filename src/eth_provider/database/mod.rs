@@ -9,6 +9,7 @@ use mongodb::{
 use serde::de::DeserializeOwned;
 
 use super::provider::EthProviderResult;
+use crate::metrics::MONGO_QUERY_DURATION;
 
 /// Wrapper around a MongoDB database
 pub struct Database(MongoDatabase);
@@ -21,6 +22,7 @@ impl Database {
 
 impl Database {
     /// Get a list of documents from a collection
+    #[tracing::instrument(skip_all, err, fields(collection = %collection))]
     pub async fn get<T>(
         &self,
         collection: &str,
@@ -30,6 +32,7 @@ impl Database {
     where
         T: DeserializeOwned + Unpin + Send + Sync,
     {
+        let _timer = MONGO_QUERY_DURATION.with_label_values(&["get", collection]).start_timer();
         let find_options = FindOptions::builder().projection(project).build();
         let collection = self.0.collection::<T>(collection);
         let result = collection.find(filter, find_options).await?.try_collect().await?;
@@ -37,6 +40,7 @@ impl Database {
     }
 
     /// Get a single document from a collection
+    #[tracing::instrument(skip_all, err, fields(collection = %collection))]
     pub async fn get_one<T>(
         &self,
         collection: &str,
@@ -46,6 +50,7 @@ impl Database {
     where
         T: DeserializeOwned + Unpin + Send + Sync,
     {
+        let _timer = MONGO_QUERY_DURATION.with_label_values(&["get_one", collection]).start_timer();
         let find_one_option = FindOneOptions::builder().sort(sort).build();
         let collection = self.0.collection::<T>(collection);
         let result = collection.find_one(filter, find_one_option).await?;
@@ -53,7 +58,9 @@ impl Database {
     }
 
     /// Count the number of documents in a collection matching the filter
+    #[tracing::instrument(skip_all, err, fields(collection = %collection))]
     pub async fn count(&self, collection: &str, filter: impl Into<Option<Document>>) -> EthProviderResult<u64> {
+        let _timer = MONGO_QUERY_DURATION.with_label_values(&["count", collection]).start_timer();
         let collection = self.0.collection::<Document>(collection);
         let count = collection.count_documents(filter, None).await?;
         Ok(count)
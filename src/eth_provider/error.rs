@@ -22,6 +22,27 @@ pub enum EthRpcErrorCode {
     JsonRpcVersionUnsupported = -32006,
 }
 
+impl From<i32> for EthRpcErrorCode {
+    fn from(code: i32) -> Self {
+        match code {
+            3 => Self::ExecutionError,
+            -32700 => Self::ParseError,
+            -32600 => Self::InvalidRequest,
+            -32601 => Self::MethodNotFound,
+            -32602 => Self::InvalidParams,
+            -32603 => Self::InternalError,
+            -32000 => Self::InvalidInput,
+            -32001 => Self::ResourceNotFound,
+            -32002 => Self::ResourceUnavailable,
+            -32003 => Self::TransactionRejected,
+            -32004 => Self::MethodNotSupported,
+            -32005 => Self::RequestLimitExceeded,
+            -32006 => Self::JsonRpcVersionUnsupported,
+            _ => Self::Unknown,
+        }
+    }
+}
+
 /// Error that can occur when interacting with the provider.
 #[derive(Debug, Error)]
 pub enum EthProviderError {
@@ -46,6 +67,12 @@ pub enum EthProviderError {
     /// Method not supported.
     #[error("Method not supported: {0}")]
     MethodNotSupported(String),
+    /// Invalid method parameters.
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+    /// Account not managed by the RPC.
+    #[error("Unknown account: {0}")]
+    UnknownAccount(reth_primitives::Address),
     /// Other error.
     #[error(transparent)]
     Other(#[from] eyre::Error),
@@ -62,6 +89,8 @@ impl From<EthProviderError> for ErrorObject<'static> {
             EthProviderError::ConversionError(msg) => rpc_err(EthRpcErrorCode::ParseError, msg.to_string()),
             EthProviderError::ValueNotFound(_) => rpc_err(EthRpcErrorCode::ResourceNotFound, msg),
             EthProviderError::MethodNotSupported(_) => rpc_err(EthRpcErrorCode::MethodNotSupported, msg),
+            EthProviderError::InvalidParams(_) => rpc_err(EthRpcErrorCode::InvalidParams, msg),
+            EthProviderError::UnknownAccount(_) => rpc_err(EthRpcErrorCode::InvalidInput, msg),
             EthProviderError::Other(msg) => rpc_err(EthRpcErrorCode::InternalError, msg.to_string()),
         }
     }
@@ -0,0 +1,138 @@
+use eyre::{eyre, Result};
+use reth_primitives::U256;
+
+/// Default number of recent blocks sampled by the gas price oracle.
+pub const DEFAULT_GAS_PRICE_ORACLE_BLOCKS: u64 = 20;
+/// Default percentile of the sampled effective priority fees suggested by the oracle.
+pub const DEFAULT_GAS_PRICE_ORACLE_PERCENTILE: f64 = 60.;
+/// Default maximum priority fee suggested by the oracle (500 gwei, same as geth).
+pub const DEFAULT_GAS_PRICE_ORACLE_MAX_PRIORITY_FEE: u64 = 500_000_000_000;
+
+/// Configuration of the gas price oracle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GasPriceOracleConfig {
+    /// Number of recent blocks sampled.
+    pub blocks: u64,
+    /// Percentile of the sampled effective priority fees that is suggested.
+    pub percentile: f64,
+    /// Minimum suggested priority fee, in wei. Also used when no transaction is sampled.
+    pub min_priority_fee: U256,
+    /// Maximum suggested priority fee, in wei.
+    pub max_priority_fee: U256,
+}
+
+impl Default for GasPriceOracleConfig {
+    fn default() -> Self {
+        Self {
+            blocks: DEFAULT_GAS_PRICE_ORACLE_BLOCKS,
+            percentile: DEFAULT_GAS_PRICE_ORACLE_PERCENTILE,
+            min_priority_fee: U256::ZERO,
+            max_priority_fee: U256::from(DEFAULT_GAS_PRICE_ORACLE_MAX_PRIORITY_FEE),
+        }
+    }
+}
+
+impl GasPriceOracleConfig {
+    /// Checks that the configuration is consistent.
+    pub fn validate(&self) -> Result<()> {
+        if self.blocks == 0 {
+            return Err(eyre!("Gas price oracle should sample at least one block"));
+        }
+        if !(0.0..=100.0).contains(&self.percentile) {
+            return Err(eyre!("Gas price oracle percentile should be in the [0, 100] range"));
+        }
+        if self.min_priority_fee > self.max_priority_fee {
+            return Err(eyre!("Gas price oracle minimum priority fee should not exceed the maximum priority fee"));
+        }
+        Ok(())
+    }
+}
+
+/// Oracle suggesting a priority fee based on the effective priority
+/// fees paid by the transactions included in the recent blocks.
+#[derive(Debug, Clone, Default)]
+pub struct GasPriceOracle {
+    config: GasPriceOracleConfig,
+}
+
+impl GasPriceOracle {
+    pub const fn new(config: GasPriceOracleConfig) -> Self {
+        Self { config }
+    }
+
+    pub const fn config(&self) -> &GasPriceOracleConfig {
+        &self.config
+    }
+
+    /// Returns the configured percentile of the sampled tips, bounded by the
+    /// configured minimum and maximum priority fees.
+    pub fn suggest_priority_fee(&self, mut tips: Vec<U256>) -> U256 {
+        if tips.is_empty() {
+            return self.config.min_priority_fee;
+        }
+
+        tips.sort_unstable();
+        let index = ((tips.len() - 1) as f64 * self.config.percentile / 100.) as usize;
+        tips[index].clamp(self.config.min_priority_fee, self.config.max_priority_fee)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oracle(percentile: f64, min_priority_fee: u64, max_priority_fee: u64) -> GasPriceOracle {
+        GasPriceOracle::new(GasPriceOracleConfig {
+            blocks: DEFAULT_GAS_PRICE_ORACLE_BLOCKS,
+            percentile,
+            min_priority_fee: U256::from(min_priority_fee),
+            max_priority_fee: U256::from(max_priority_fee),
+        })
+    }
+
+    #[test]
+    fn test_suggest_priority_fee() {
+        // Given
+        let tips = [5, 1, 4, 2, 3].into_iter().map(U256::from).collect::<Vec<_>>();
+
+        // When
+        let lowest = oracle(0., 0, 100).suggest_priority_fee(tips.clone());
+        let median = oracle(50., 0, 100).suggest_priority_fee(tips.clone());
+        let highest = oracle(100., 0, 100).suggest_priority_fee(tips);
+
+        // Then
+        assert_eq!(lowest, U256::from(1));
+        assert_eq!(median, U256::from(3));
+        assert_eq!(highest, U256::from(5));
+    }
+
+    #[test]
+    fn test_suggest_priority_fee_bounds() {
+        // Given
+        let tips = [5, 1, 4, 2, 3].into_iter().map(U256::from).collect::<Vec<_>>();
+
+        // When
+        let floored = oracle(0., 2, 100).suggest_priority_fee(tips.clone());
+        let capped = oracle(100., 0, 4).suggest_priority_fee(tips);
+        let empty = oracle(50., 7, 100).suggest_priority_fee(vec![]);
+
+        // Then
+        assert_eq!(floored, U256::from(2));
+        assert_eq!(capped, U256::from(4));
+        assert_eq!(empty, U256::from(7));
+    }
+
+    #[test]
+    fn test_validate_config() {
+        assert!(GasPriceOracleConfig::default().validate().is_ok());
+        assert!(GasPriceOracleConfig { blocks: 0, ..Default::default() }.validate().is_err());
+        assert!(GasPriceOracleConfig { percentile: 101., ..Default::default() }.validate().is_err());
+        assert!(GasPriceOracleConfig {
+            min_priority_fee: U256::from(2),
+            max_priority_fee: U256::from(1),
+            ..Default::default()
+        }
+        .validate()
+        .is_err());
+    }
+}
@@ -1,7 +1,10 @@
+pub mod cache;
 pub mod constant;
 pub mod contracts;
 pub mod database;
 pub mod error;
+pub mod gas_oracle;
 pub mod provider;
+pub mod single_flight;
 pub mod starknet;
 pub mod utils;
@@ -1,12 +1,17 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use alloy_rlp::Decodable as _;
 use async_trait::async_trait;
 use auto_impl::auto_impl;
-use cainome::cairo_serde::CairoArrayLegacy;
+use cainome::cairo_serde::call::FCall;
+use cainome::cairo_serde::{CairoArrayLegacy, CairoSerde};
 use eyre::eyre;
 use eyre::Result;
 use itertools::Itertools;
 use mongodb::bson::doc;
 use reth_primitives::constants::EMPTY_ROOT_HASH;
+use reth_primitives::keccak256;
 use reth_primitives::revm_primitives::FixedBytes;
 use reth_primitives::Address;
 use reth_primitives::BlockId;
@@ -25,13 +30,16 @@ use reth_rpc_types::TransactionRequest;
 use reth_rpc_types::U64HexOrNumber;
 use reth_rpc_types::ValueOrArray;
 use reth_rpc_types::{Block, BlockTransactions, RichBlock};
+use reth_rpc_types::{EIP1186AccountProofResponse, EIP1186StorageProof};
 use reth_rpc_types::{SyncInfo, SyncStatus};
 use starknet::core::types::BroadcastedInvokeTransaction;
 use starknet::core::types::SyncStatusType;
 use starknet::core::types::ValueOutOfRangeError;
 use starknet::core::utils::get_storage_var_address;
 use starknet_crypto::FieldElement;
+use tokio::sync::OnceCell;
 
+use super::cache::{TtlCell, DEFAULT_VALUES_TTL};
 use super::constant::CALL_REQUEST_GAS_LIMIT;
 use super::database::types::log::StoredLog;
 use super::database::types::{
@@ -40,17 +48,20 @@ use super::database::types::{
 };
 use super::database::Database;
 use super::error::EthProviderError;
+use super::gas_oracle::{GasPriceOracle, GasPriceOracleConfig};
+use super::single_flight::{CallKey, SingleFlight};
 use super::starknet::kakarot_core;
 use super::starknet::kakarot_core::core::{KakarotCoreReader, Uint256};
 use super::starknet::kakarot_core::to_starknet_transaction;
 use super::starknet::kakarot_core::{
-    contract_account::ContractAccountReader, proxy::ProxyReader, starknet_address, CONTRACT_ACCOUNT_CLASS_HASH,
-    EXTERNALLY_OWNED_ACCOUNT_CLASS_HASH, KAKAROT_ADDRESS,
+    contract_account::ContractAccountReader, proxy::ProxyReader, starknet_address, KakarotDeployment,
 };
+use super::starknet::proof::{ProofNode, StarknetProofClient};
 use super::starknet::ERC20Reader;
 use super::starknet::STARKNET_NATIVE_TOKEN;
 use super::utils::{
-    contract_not_found, entrypoint_not_found, into_filter, iter_into, split_u256, try_from_u8_iterator,
+    contract_not_found, effective_tip, entrypoint_not_found, into_filter, iter_into, rewards_for_percentiles,
+    split_u256, try_from_u8_iterator, validate_reward_percentiles,
 };
 use crate::eth_provider::utils::format_hex;
 use crate::into_via_try_wrapper;
@@ -59,6 +70,7 @@ use crate::models::block::EthBlockId;
 use crate::models::block::EthBlockNumberOrTag;
 use crate::models::errors::ConversionError;
 use crate::models::felt::Felt252Wrapper;
+use crate::models::health::ProviderHealth;
 
 pub type EthProviderResult<T> = Result<T, EthProviderError>;
 
@@ -72,6 +84,8 @@ pub trait EthereumProvider {
     async fn syncing(&self) -> EthProviderResult<SyncStatus>;
     /// Returns the chain id.
     async fn chain_id(&self) -> EthProviderResult<Option<U64>>;
+    /// Returns the coinbase of Kakarot, the address receiving the fees of the transactions.
+    async fn coinbase(&self) -> EthProviderResult<Address>;
     /// Returns a block by hash. Block can be full or just the hashes of the transactions.
     async fn block_by_hash(&self, hash: B256, full: bool) -> EthProviderResult<Option<RichBlock>>;
     /// Returns a block by number. Block can be full or just the hashes of the transactions.
@@ -131,16 +145,40 @@ pub trait EthereumProvider {
     ) -> EthProviderResult<FeeHistory>;
     /// Send a raw transaction to the network and returns the transactions hash.
     async fn send_raw_transaction(&self, transaction: Bytes) -> EthProviderResult<B256>;
+    /// Returns the current gas price, i.e. the base fee plus the suggested priority fee.
     async fn gas_price(&self) -> EthProviderResult<U256>;
+    /// Returns the priority fee per gas suggested by the gas price oracle.
+    async fn max_priority_fee_per_gas(&self) -> EthProviderResult<U256>;
     async fn block_receipts(&self, block_id: Option<BlockId>) -> EthProviderResult<Option<Vec<TransactionReceipt>>>;
+    /// Returns the account and storage values of the address, along with the Starknet storage proofs.
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<B256>,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<EIP1186AccountProofResponse>;
+    /// Returns the health of the database and of the Starknet provider.
+    async fn health(&self) -> ProviderHealth;
 }
 
 /// Structure that implements the EthereumProvider trait.
 /// Uses an access to a database to certain data, while
 /// the rest is fetched from the Starknet Provider.
+///
+/// The static values, i.e. the chain id and the coinbase, are fetched once, while the
+/// short-lived ones, i.e. the base fee and the priority fee, are cached for a short duration.
+/// The identical Starknet calls in flight are merged into a single request.
 pub struct EthDataProvider<SP: starknet::providers::Provider> {
     database: Database,
     starknet_provider: SP,
+    kakarot: KakarotDeployment,
+    gas_price_oracle: GasPriceOracle,
+    proof_client: Option<StarknetProofClient>,
+    chain_id: OnceCell<Option<U64>>,
+    coinbase: OnceCell<Address>,
+    base_fee: TtlCell<U256>,
+    priority_fee: TtlCell<U256>,
+    calls: SingleFlight<CallKey, Vec<FieldElement>>,
 }
 
 #[async_trait]
@@ -149,18 +187,9 @@ where
     SP: starknet::providers::Provider + Send + Sync,
 {
     async fn block_number(&self) -> EthProviderResult<U64> {
-        let filter = doc! {};
-        let sort = doc! { "header.number": -1 };
-        let header: Option<StoredHeader> = self.database.get_one("headers", filter, sort).await?;
-        let block_number = match header {
+        let block_number = match self.indexed_block_number().await? {
             None => U64::from(self.starknet_provider.block_number().await?), // in case the database is empty, use the starknet provider
-            Some(header) => {
-                let number = header.header.number.ok_or(EthProviderError::ValueNotFound("Block".to_string()))?;
-                let number: u64 = number
-                    .try_into()
-                    .map_err(|_| ConversionError::ValueOutOfRange("Block number too large".to_string()))?;
-                U64::from(number)
-            }
+            Some(number) => U64::from(number),
         };
         Ok(block_number)
     }
@@ -189,11 +218,35 @@ where
         }
     }
 
-    // TODO cache chain id
     async fn chain_id(&self) -> EthProviderResult<Option<U64>> {
-        let chain_id = self.starknet_provider.chain_id().await?;
-        let chain_id: Option<u64> = chain_id.try_into().ok();
-        Ok(chain_id.map(U64::from))
+        let chain_id = self
+            .chain_id
+            .get_or_try_init(|| async {
+                let chain_id = self.starknet_provider.chain_id().await?;
+                let chain_id: Option<u64> = chain_id.try_into().ok();
+                EthProviderResult::Ok(chain_id.map(U64::from))
+            })
+            .await?;
+        Ok(*chain_id)
+    }
+
+    async fn coinbase(&self) -> EthProviderResult<Address> {
+        let coinbase = self
+            .coinbase
+            .get_or_try_init(|| async {
+                let key = get_storage_var_address("coinbase", &[]).expect("Storage var name is not ASCII");
+                let coinbase = self
+                    .starknet_provider
+                    .get_storage_at(
+                        self.kakarot.kakarot_address,
+                        key,
+                        starknet::core::types::BlockId::Tag(starknet::core::types::BlockTag::Latest),
+                    )
+                    .await?;
+                EthProviderResult::Ok(Address::try_from(Felt252Wrapper::from(coinbase))?)
+            })
+            .await?;
+        Ok(*coinbase)
     }
 
     async fn block_by_hash(&self, hash: B256, full: bool) -> EthProviderResult<Option<RichBlock>> {
@@ -280,15 +333,7 @@ where
 
     async fn balance(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
-
-        let eth_contract = ERC20Reader::new(*STARKNET_NATIVE_TOKEN, &self.starknet_provider);
-
-        let address = starknet_address(address);
-        let balance = eth_contract.balanceOf(&address).block_id(starknet_block_id).call().await?.balance;
-
-        let low: U256 = into_via_wrapper!(balance.low);
-        let high: U256 = into_via_wrapper!(balance.high);
-        Ok(low + (high << 128))
+        self.balance_at(address, starknet_block_id).await
     }
 
     async fn storage_at(
@@ -298,58 +343,17 @@ where
         block_id: Option<BlockId>,
     ) -> EthProviderResult<B256> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
-
-        let address = starknet_address(address);
-        let contract = ContractAccountReader::new(address, &self.starknet_provider);
-
-        let keys = split_u256::<FieldElement>(index.0);
-        let storage_address = get_storage_var_address("storage_", &keys).expect("Storage var name is not ASCII");
-
-        let storage = contract.storage(&storage_address).block_id(starknet_block_id).call().await?.value;
-
-        let low: U256 = into_via_wrapper!(storage.low);
-        let high: U256 = into_via_wrapper!(storage.high);
-        let storage: U256 = low + (high << 128);
-
-        Ok(storage.into())
+        self.storage_value_at(address, index, starknet_block_id).await
     }
 
     async fn transaction_count(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<U256> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
-
-        let address = starknet_address(address);
-        let proxy = ProxyReader::new(address, &self.starknet_provider);
-        let maybe_class_hash = proxy.get_implementation().block_id(starknet_block_id).call().await;
-
-        if contract_not_found(&maybe_class_hash) {
-            return Ok(U256::ZERO);
-        }
-        let class_hash = maybe_class_hash?.implementation;
-
-        let nonce = if class_hash == *EXTERNALLY_OWNED_ACCOUNT_CLASS_HASH {
-            self.starknet_provider.get_nonce(starknet_block_id, address).await?
-        } else if class_hash == *CONTRACT_ACCOUNT_CLASS_HASH {
-            let contract = ContractAccountReader::new(address, &self.starknet_provider);
-            contract.get_nonce().block_id(starknet_block_id).call().await?.nonce
-        } else {
-            FieldElement::ZERO
-        };
-        Ok(into_via_wrapper!(nonce))
+        self.nonce_at(address, starknet_block_id).await
     }
 
     async fn get_code(&self, address: Address, block_id: Option<BlockId>) -> EthProviderResult<Bytes> {
         let starknet_block_id = self.to_starknet_block_id(block_id).await?;
-
-        let address = starknet_address(address);
-        let contract = ContractAccountReader::new(address, &self.starknet_provider);
-        let bytecode = contract.bytecode().block_id(starknet_block_id).call().await;
-
-        if contract_not_found(&bytecode) || entrypoint_not_found(&bytecode) {
-            return Ok(Bytes::default());
-        }
-
-        let bytecode = bytecode?.bytecode.0;
-        Ok(Bytes::from(try_from_u8_iterator::<_, Vec<u8>>(bytecode)))
+        self.code_at(address, starknet_block_id).await
     }
 
     async fn get_logs(&self, filter: Filter) -> EthProviderResult<FilterChanges> {
@@ -420,12 +424,16 @@ where
         &self,
         block_count: U64HexOrNumber,
         newest_block: BlockNumberOrTag,
-        _reward_percentiles: Option<Vec<f64>>,
+        reward_percentiles: Option<Vec<f64>>,
     ) -> EthProviderResult<FeeHistory> {
         if block_count.to() == 0 {
             return Ok(FeeHistory::default());
         }
 
+        if let Some(percentiles) = &reward_percentiles {
+            validate_reward_percentiles(percentiles)?;
+        }
+
         let end_block = self.tag_into_block_number(newest_block).await?;
         let end_block = end_block.to::<u64>();
         let end_block_plus = end_block.saturating_add(1);
@@ -456,6 +464,24 @@ where
             })
             .collect::<Vec<_>>();
 
+        let reward = match reward_percentiles {
+            Some(percentiles) => {
+                let mut receipts = self.receipts_by_block(start_block, end_block).await?;
+                let reward = blocks
+                    .iter()
+                    .map(|header| {
+                        let base_fee = header.header.base_fee_per_gas.unwrap_or_default();
+                        let receipts =
+                            header.header.number.and_then(|number| receipts.remove(&number)).unwrap_or_default();
+                        let tips = receipts.iter().map(|receipt| effective_tip(receipt, base_fee)).collect();
+                        rewards_for_percentiles(tips, &percentiles)
+                    })
+                    .collect();
+                Some(reward)
+            }
+            None => None,
+        };
+
         let mut base_fee_per_gas =
             blocks.iter().map(|header| header.header.base_fee_per_gas.unwrap_or_default()).collect::<Vec<_>>();
         // TODO(EIP1559): Remove this when proper base fee computation: if gas_ratio > 50%, increase base_fee_per_gas
@@ -465,7 +491,7 @@ where
             base_fee_per_gas,
             gas_used_ratio,
             oldest_block: U256::from(start_block),
-            reward: Some(vec![]),
+            reward,
             ..Default::default()
         })
     }
@@ -501,7 +527,7 @@ where
             max_fee = u64::MAX;
         }
 
-        let transaction = to_starknet_transaction(&transaction_signed, chain_id, signer, max_fee)?;
+        let transaction = to_starknet_transaction(&self.kakarot, &transaction_signed, chain_id, signer, max_fee)?;
 
         // If the contract is not found, we need to deploy it.
         #[cfg(feature = "hive")]
@@ -517,13 +543,14 @@ where
                 proxy.get_implementation().block_id(starknet::core::types::BlockId::Tag(BlockTag::Latest)).call().await;
 
             if contract_not_found(&maybe_class_hash) {
+                let deploy_wallet = DEPLOY_WALLET.get().ok_or_else(|| eyre::eyre!("Missing deploy wallet"))?;
                 let execution = Execution::new(
                     vec![Call {
-                        to: *KAKAROT_ADDRESS,
+                        to: self.kakarot.kakarot_address,
                         selector: get_selector_from_name("deploy_externally_owned_account").unwrap(),
                         calldata: vec![into_via_wrapper!(signer)],
                     }],
-                    &*DEPLOY_WALLET,
+                    deploy_wallet,
                 );
 
                 let mut nonce = DEPLOY_WALLET_NONCE.lock().await;
@@ -567,9 +594,21 @@ where
     }
 
     async fn gas_price(&self) -> EthProviderResult<U256> {
-        let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
-        let gas_price = kakarot_contract.get_base_fee().call().await?.base_fee;
-        Ok(into_via_wrapper!(gas_price))
+        let base_fee = self
+            .base_fee
+            .get_or_try_insert_with(|| async {
+                let kakarot_contract = KakarotCoreReader::new(self.kakarot.kakarot_address, &self.starknet_provider);
+                let base_fee = self.coalesced_call(kakarot_contract.get_base_fee()).await?.base_fee;
+                EthProviderResult::Ok(into_via_wrapper!(base_fee))
+            })
+            .await?;
+
+        let priority_fee = self.max_priority_fee_per_gas().await?;
+        Ok(base_fee.saturating_add(priority_fee))
+    }
+
+    async fn max_priority_fee_per_gas(&self) -> EthProviderResult<U256> {
+        self.priority_fee.get_or_try_insert_with(|| self.suggest_priority_fee()).await
     }
 
     async fn block_receipts(&self, block_id: Option<BlockId>) -> EthProviderResult<Option<Vec<TransactionReceipt>>> {
@@ -598,14 +637,124 @@ where
             }
         }
     }
+
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<B256>,
+        block_id: Option<BlockId>,
+    ) -> EthProviderResult<EIP1186AccountProofResponse> {
+        let proof_client =
+            self.proof_client.as_ref().ok_or(EthProviderError::MethodNotSupported("eth_getProof".to_string()))?;
+        // Proofs are only available for sealed blocks: the tags are resolved to the latest sealed
+        // block, at which both the proof and the values of the account are read.
+        let starknet_block_id = match self.to_starknet_block_id(block_id).await? {
+            starknet::core::types::BlockId::Tag(_) => {
+                starknet::core::types::BlockId::Number(self.starknet_provider.block_number().await?)
+            }
+            starknet_block_id => starknet_block_id,
+        };
+
+        // Each EVM storage slot is stored over two Starknet storage slots, holding
+        // respectively the low and high 128 bits of the value.
+        let storage_keys = keys
+            .iter()
+            .flat_map(|key| {
+                let keys = split_u256::<FieldElement>(U256::from_be_bytes(key.0));
+                let low = get_storage_var_address("storage_", &keys).expect("Storage var name is not ASCII");
+                [low, low + FieldElement::ONE]
+            })
+            .collect::<Vec<_>>();
+
+        let proof =
+            proof_client.get_proof(starknet_block_id, starknet_address(&self.kakarot, address), &storage_keys).await?;
+
+        let balance = self.balance_at(address, starknet_block_id).await?;
+        let nonce = self.nonce_at(address, starknet_block_id).await?;
+        let code = self.code_at(address, starknet_block_id).await?;
+        let code_hash = keccak256(&code);
+
+        let (storage_hash, storage_proofs) = proof
+            .contract_data
+            .map(|data| (B256::from(data.root.to_bytes_be()), data.storage_proofs))
+            .unwrap_or((EMPTY_ROOT_HASH, vec![]));
+
+        let mut storage_proof = Vec::with_capacity(keys.len());
+        for (i, key) in keys.into_iter().enumerate() {
+            let value = self.storage_value_at(address, JsonStorageKey(key), starknet_block_id).await?;
+            let proof = storage_proofs.iter().skip(2 * i).take(2).flatten().map(ProofNode::encode).collect();
+            storage_proof.push(EIP1186StorageProof {
+                key: JsonStorageKey(key),
+                value: U256::from_be_bytes(value.0),
+                proof,
+            });
+        }
+
+        Ok(EIP1186AccountProofResponse {
+            address,
+            balance,
+            code_hash,
+            nonce: U64::from(nonce.to::<u64>()),
+            storage_hash,
+            account_proof: proof.contract_proof.iter().map(ProofNode::encode).collect(),
+            storage_proof,
+        })
+    }
+
+    async fn health(&self) -> ProviderHealth {
+        let (mongo, starknet) =
+            futures::future::join(self.indexed_block_number(), self.starknet_provider.block_number()).await;
+        ProviderHealth { mongo: mongo.into(), starknet: starknet.map(Some).map_err(EthProviderError::from).into() }
+    }
 }
 
 impl<SP> EthDataProvider<SP>
 where
     SP: starknet::providers::Provider + Send + Sync,
 {
-    pub const fn new(database: Database, starknet_provider: SP) -> Self {
-        Self { database, starknet_provider }
+    pub fn new(database: Database, starknet_provider: SP, kakarot: KakarotDeployment) -> Self {
+        Self {
+            database,
+            starknet_provider,
+            kakarot,
+            gas_price_oracle: GasPriceOracle::default(),
+            proof_client: None,
+            chain_id: OnceCell::new(),
+            coinbase: OnceCell::new(),
+            base_fee: TtlCell::new(DEFAULT_VALUES_TTL),
+            priority_fee: TtlCell::new(DEFAULT_VALUES_TTL),
+            calls: SingleFlight::default(),
+        }
+    }
+
+    /// Sets the configuration of the gas price oracle used by `eth_gasPrice` and `eth_maxPriorityFeePerGas`.
+    #[must_use]
+    pub fn with_gas_price_oracle_config(mut self, config: GasPriceOracleConfig) -> Self {
+        self.gas_price_oracle = GasPriceOracle::new(config);
+        self
+    }
+
+    /// Sets the client used to fetch the Starknet storage proofs for `eth_getProof`.
+    #[must_use]
+    pub fn with_proof_client(mut self, proof_client: StarknetProofClient) -> Self {
+        self.proof_client = Some(proof_client);
+        self
+    }
+
+    /// Sets the duration for which the base fee and the priority fee are cached, 0 disabling
+    /// their caches.
+    #[must_use]
+    pub fn with_values_ttl(mut self, ttl: Duration) -> Self {
+        self.base_fee = TtlCell::new(ttl);
+        self.priority_fee = TtlCell::new(ttl);
+        self
+    }
+
+    /// Fetches the static values, i.e. the chain id and the coinbase, which are kept for the
+    /// lifetime of the provider.
+    pub async fn load_static_values(&self) -> EthProviderResult<()> {
+        futures::future::try_join(self.chain_id(), self.coinbase()).await?;
+        Ok(())
     }
 
     #[cfg(feature = "testing")]
@@ -613,6 +762,58 @@ where
         &self.starknet_provider
     }
 
+    /// Returns the Kakarot deployment the provider reads from.
+    pub const fn kakarot_deployment(&self) -> &KakarotDeployment {
+        &self.kakarot
+    }
+
+    /// Returns the number of Starknet blocks which are not indexed in the database yet.
+    pub async fn indexer_lag(&self) -> EthProviderResult<u64> {
+        let starknet_head = self.starknet_provider.block_number().await?;
+        let indexed_head = self.indexed_block_number().await?.unwrap_or_default();
+        Ok(starknet_head.saturating_sub(indexed_head))
+    }
+
+    /// Returns the priority fee suggested by the gas price oracle from the tips of the latest
+    /// blocks.
+    async fn suggest_priority_fee(&self) -> EthProviderResult<U256> {
+        let end_block = self.block_number().await?.to::<u64>();
+        let start_block = end_block.saturating_sub(self.gas_price_oracle.config().blocks.saturating_sub(1));
+
+        let header_filter =
+            doc! {"header.number": {"$gte": format_hex(start_block, 64), "$lte": format_hex(end_block, 64)}};
+        let headers: Vec<StoredHeader> = self.database.get("headers", header_filter, None).await?;
+        let mut receipts = self.receipts_by_block(start_block, end_block).await?;
+
+        let tips = headers
+            .iter()
+            .flat_map(|header| {
+                let base_fee = header.header.base_fee_per_gas.unwrap_or_default();
+                let receipts = header.header.number.and_then(|number| receipts.remove(&number)).unwrap_or_default();
+                receipts.into_iter().map(move |receipt| effective_tip(&receipt, base_fee).0)
+            })
+            .collect();
+
+        Ok(self.gas_price_oracle.suggest_priority_fee(tips))
+    }
+
+    /// Returns the number of the highest header in the database, if any.
+    async fn indexed_block_number(&self) -> EthProviderResult<Option<u64>> {
+        let filter = doc! {};
+        let sort = doc! { "header.number": -1 };
+        let header: Option<StoredHeader> = self.database.get_one("headers", filter, sort).await?;
+        header
+            .map(|header| {
+                let number = header.header.number.ok_or(EthProviderError::ValueNotFound("Block".to_string()))?;
+                let number: u64 = number
+                    .try_into()
+                    .map_err(|_| ConversionError::ValueOutOfRange("Block number too large".to_string()))?;
+                Ok(number)
+            })
+            .transpose()
+    }
+
+    #[tracing::instrument(skip_all, err)]
     async fn call_helper(
         &self,
         request: TransactionRequest,
@@ -653,8 +854,8 @@ where
             }
         };
 
-        let kakarot_contract = KakarotCoreReader::new(*KAKAROT_ADDRESS, &self.starknet_provider);
-        let call_output = kakarot_contract
+        let kakarot_contract = KakarotCoreReader::new(self.kakarot.kakarot_address, &self.starknet_provider);
+        let call = kakarot_contract
             .eth_call(
                 &nonce,
                 &from,
@@ -667,9 +868,8 @@ where
                 &FieldElement::ZERO,
                 &CairoArrayLegacy(vec![]),
             )
-            .block_id(starknet_block_id)
-            .call()
-            .await?;
+            .block_id(starknet_block_id);
+        let call_output = self.coalesced_call(call).await?;
 
         let return_data = call_output.return_data;
         if call_output.success == FieldElement::ZERO {
@@ -684,6 +884,20 @@ where
         Ok((return_data, gas_used))
     }
 
+    /// Calls the Starknet contract, merging the call into the identical call in flight if any.
+    async fn coalesced_call<T>(&self, call: FCall<'_, SP, T>) -> Result<T, cainome::cairo_serde::Error>
+    where
+        T: CairoSerde<RustType = T>,
+    {
+        let key = CallKey::new(&call.call_raw, call.block_id);
+        let output = self
+            .calls
+            .run(key, self.starknet_provider.call(call.call_raw, call.block_id))
+            .await
+            .map_err(cainome::cairo_serde::Error::Provider)?;
+        T::cairo_deserialize(&output, 0)
+    }
+
     /// Check if a block exists in the database.
     async fn block_exists(&self, block_id: BlockHashOrNumber) -> EthProviderResult<bool> {
         Ok(self.header(block_id).await?.is_some())
@@ -698,6 +912,23 @@ where
         self.database.get_one("headers", filter, None).await
     }
 
+    /// Get the receipts of the blocks in the range [start_block, end_block], grouped by block number.
+    async fn receipts_by_block(
+        &self,
+        start_block: u64,
+        end_block: u64,
+    ) -> EthProviderResult<HashMap<U256, Vec<TransactionReceipt>>> {
+        let filter =
+            doc! {"receipt.blockNumber": {"$gte": format_hex(start_block, 64), "$lte": format_hex(end_block, 64)}};
+        let receipts: Vec<StoredTransactionReceipt> = self.database.get("receipts", filter, None).await?;
+
+        Ok(receipts
+            .into_iter()
+            .map(TransactionReceipt::from)
+            .filter_map(|receipt| receipt.block_number.map(|number| (number, receipt)))
+            .into_group_map())
+    }
+
     /// Get a block from the database based on a block hash or number.
     /// If full is true, the block will contain the full transactions, otherwise just the hashes
     async fn block(&self, block_id: BlockHashOrNumber, full: bool) -> EthProviderResult<Option<RichBlock>> {
@@ -779,6 +1010,88 @@ where
         }
     }
 
+    /// Returns the balance of the address at the Starknet block.
+    async fn balance_at(
+        &self,
+        address: Address,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<U256> {
+        let eth_contract = ERC20Reader::new(*STARKNET_NATIVE_TOKEN, &self.starknet_provider);
+
+        let address = starknet_address(&self.kakarot, address);
+        let balance = self.coalesced_call(eth_contract.balanceOf(&address).block_id(starknet_block_id)).await?.balance;
+
+        let low: U256 = into_via_wrapper!(balance.low);
+        let high: U256 = into_via_wrapper!(balance.high);
+        Ok(low + (high << 128))
+    }
+
+    /// Returns the value of the storage slot of the address at the Starknet block.
+    async fn storage_value_at(
+        &self,
+        address: Address,
+        index: JsonStorageKey,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<B256> {
+        let address = starknet_address(&self.kakarot, address);
+        let contract = ContractAccountReader::new(address, &self.starknet_provider);
+
+        let keys = split_u256::<FieldElement>(index.0);
+        let storage_address = get_storage_var_address("storage_", &keys).expect("Storage var name is not ASCII");
+
+        let storage = self.coalesced_call(contract.storage(&storage_address).block_id(starknet_block_id)).await?.value;
+
+        let low: U256 = into_via_wrapper!(storage.low);
+        let high: U256 = into_via_wrapper!(storage.high);
+        let storage: U256 = low + (high << 128);
+
+        Ok(storage.into())
+    }
+
+    /// Returns the nonce of the address at the Starknet block.
+    async fn nonce_at(
+        &self,
+        address: Address,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<U256> {
+        let address = starknet_address(&self.kakarot, address);
+        let proxy = ProxyReader::new(address, &self.starknet_provider);
+        let maybe_class_hash = self.coalesced_call(proxy.get_implementation().block_id(starknet_block_id)).await;
+
+        if contract_not_found(&maybe_class_hash) {
+            return Ok(U256::ZERO);
+        }
+        let class_hash = maybe_class_hash?.implementation;
+
+        let nonce = if class_hash == self.kakarot.externally_owned_account_class_hash {
+            self.starknet_provider.get_nonce(starknet_block_id, address).await?
+        } else if class_hash == self.kakarot.contract_account_class_hash {
+            let contract = ContractAccountReader::new(address, &self.starknet_provider);
+            self.coalesced_call(contract.get_nonce().block_id(starknet_block_id)).await?.nonce
+        } else {
+            FieldElement::ZERO
+        };
+        Ok(into_via_wrapper!(nonce))
+    }
+
+    /// Returns the code of the address at the Starknet block.
+    async fn code_at(
+        &self,
+        address: Address,
+        starknet_block_id: starknet::core::types::BlockId,
+    ) -> EthProviderResult<Bytes> {
+        let address = starknet_address(&self.kakarot, address);
+        let contract = ContractAccountReader::new(address, &self.starknet_provider);
+        let bytecode = self.coalesced_call(contract.bytecode().block_id(starknet_block_id)).await;
+
+        if contract_not_found(&bytecode) || entrypoint_not_found(&bytecode) {
+            return Ok(Bytes::default());
+        }
+
+        let bytecode = bytecode?.bytecode.0;
+        Ok(Bytes::from(try_from_u8_iterator::<_, Vec<u8>>(bytecode)))
+    }
+
     /// Convert the given BlockNumberOrTag into a block number
     async fn tag_into_block_number(&self, tag: BlockNumberOrTag) -> EthProviderResult<U64> {
         match tag {
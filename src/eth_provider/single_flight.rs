@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Mutex, PoisonError};
+
+use starknet::core::types::{BlockId, BlockTag, FunctionCall};
+use starknet_crypto::FieldElement;
+use tokio::sync::broadcast;
+
+use crate::metrics::STARKNET_COALESCED_CALLS;
+
+type Flights<K, V> = Mutex<HashMap<K, broadcast::Sender<V>>>;
+
+/// Merges the identical calls in flight into a single one: the first caller of a key makes the
+/// call, while the following ones wait for its result.
+///
+/// Failures aren't shared: when a call fails, or is cancelled, the first of its waiting callers
+/// makes its own call, which the others wait for in turn.
+#[derive(Debug)]
+pub struct SingleFlight<K, V> {
+    flights: Flights<K, V>,
+}
+
+impl<K, V> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        Self { flights: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+{
+    /// Returns the result of the call in flight for the key if any, else the result of `call`.
+    pub async fn run<F, E>(&self, key: K, call: F) -> Result<V, E>
+    where
+        F: Future<Output = Result<V, E>>,
+    {
+        loop {
+            let receiver = {
+                let mut flights = self.flights.lock().unwrap_or_else(PoisonError::into_inner);
+                match flights.get(&key) {
+                    Some(sender) => Some(sender.subscribe()),
+                    None => {
+                        flights.insert(key.clone(), broadcast::channel(1).0);
+                        None
+                    }
+                }
+            };
+
+            let Some(mut receiver) = receiver else { break };
+            // The channel is closed without a value if the call failed, the waiting callers then
+            // race for the next flight.
+            if let Ok(value) = receiver.recv().await {
+                STARKNET_COALESCED_CALLS.inc();
+                return Ok(value);
+            }
+        }
+
+        let flight = Flight { flights: &self.flights, key: Some(key) };
+        let result = call.await;
+        if let Ok(value) = &result {
+            flight.land(value.clone());
+        }
+        result
+    }
+}
+
+/// Call in flight, removed from the flights once it completes or when it is dropped, e.g. if
+/// its caller is cancelled.
+struct Flight<'a, K: Hash + Eq, V> {
+    flights: &'a Flights<K, V>,
+    key: Option<K>,
+}
+
+impl<K: Hash + Eq, V> Flight<'_, K, V> {
+    /// Sends the result of the call to the waiting callers.
+    fn land(mut self, value: V) {
+        if let Some(sender) = self.remove() {
+            let _ = sender.send(value);
+        }
+    }
+
+    fn remove(&mut self) -> Option<broadcast::Sender<V>> {
+        let key = self.key.take()?;
+        self.flights.lock().unwrap_or_else(PoisonError::into_inner).remove(&key)
+    }
+}
+
+impl<K: Hash + Eq, V> Drop for Flight<'_, K, V> {
+    fn drop(&mut self) {
+        self.remove();
+    }
+}
+
+/// Key of a Starknet call: its contract, selector, calldata and block.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallKey {
+    contract_address: FieldElement,
+    entry_point_selector: FieldElement,
+    calldata: Vec<FieldElement>,
+    block: CallBlock,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CallBlock {
+    Hash(FieldElement),
+    Number(u64),
+    Latest,
+    Pending,
+}
+
+impl CallKey {
+    pub fn new(call: &FunctionCall, block_id: BlockId) -> Self {
+        let block = match block_id {
+            BlockId::Hash(hash) => CallBlock::Hash(hash),
+            BlockId::Number(number) => CallBlock::Number(number),
+            BlockId::Tag(BlockTag::Latest) => CallBlock::Latest,
+            BlockId::Tag(BlockTag::Pending) => CallBlock::Pending,
+        };
+        Self {
+            contract_address: call.contract_address,
+            entry_point_selector: call.entry_point_selector,
+            calldata: call.calldata.clone(),
+            block,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_single_flight() {
+        // Given
+        let single_flight = SingleFlight::default();
+        let calls = &AtomicU32::new(0);
+        let call = |value: u32| async move {
+            calls.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, ()>(value)
+        };
+
+        // When
+        let (first, second, other) = tokio::join!(
+            single_flight.run("balanceOf", call(1)),
+            single_flight.run("balanceOf", call(2)),
+            single_flight.run("eth_call", call(3)),
+        );
+
+        // Then
+        // The second call got the result of the first one
+        assert_eq!((first, second, other), (Ok(1), Ok(1), Ok(3)));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert!(single_flight.flights.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_failure() {
+        // Given
+        let single_flight = SingleFlight::default();
+        let failing = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Err("unavailable")
+        };
+
+        // When
+        let (first, second) = tokio::join!(
+            single_flight.run("balanceOf", failing),
+            single_flight.run("balanceOf", async { Ok::<_, &str>(2) })
+        );
+
+        // Then
+        // The failure isn't shared, the second caller made its own call
+        assert_eq!((first, second), (Err("unavailable"), Ok(2)));
+        assert!(single_flight.flights.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_failure_with_concurrent_callers() {
+        // Given
+        let single_flight = SingleFlight::default();
+        let calls = &AtomicU32::new(0);
+        let failing = async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Err("unavailable")
+        };
+        let call = |value: u32| async move {
+            calls.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            Ok::<_, &str>(value)
+        };
+
+        // When
+        let (first, second, third, fourth) = tokio::join!(
+            single_flight.run("balanceOf", failing),
+            single_flight.run("balanceOf", call(2)),
+            single_flight.run("balanceOf", call(3)),
+            single_flight.run("balanceOf", call(4)),
+        );
+
+        // Then
+        // A single waiting caller made its own call, the others got its result
+        assert_eq!(first, Err("unavailable"));
+        assert!(second.is_ok() && second == third && third == fourth);
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        assert!(single_flight.flights.lock().unwrap().is_empty());
+    }
+}
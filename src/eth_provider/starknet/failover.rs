@@ -0,0 +1,450 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use starknet::providers::jsonrpc::{HttpTransportError, JsonRpcMethod, JsonRpcResponse, JsonRpcTransport};
+use url::Url;
+
+use crate::metrics::STARKNET_UPSTREAM_HEALTHY;
+
+/// Default number of retries of a failed request.
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry, doubled at each retry.
+pub const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Default number of consecutive failures after which an upstream is ejected.
+pub const DEFAULT_MAX_FAILURES: u32 = 3;
+
+/// Default interval between two health checks of the upstreams.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Default timeout of the connection to an upstream.
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default timeout of a request to an upstream, from the connection to the end of the response.
+pub const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Retry and health check policy of the [`FailoverTransport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FailoverConfig {
+    /// Number of retries of a failed request.
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled at each retry.
+    pub initial_backoff: Duration,
+    /// Number of consecutive failures after which an upstream is ejected.
+    pub max_failures: u32,
+    /// Interval between two health checks of the upstreams.
+    pub health_check_interval: Duration,
+    /// Timeout of the connection to an upstream.
+    pub connect_timeout: Duration,
+    /// Timeout of a request to an upstream, after which it counts as a failure of the upstream.
+    pub request_timeout: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            max_failures: DEFAULT_MAX_FAILURES,
+            health_check_interval: DEFAULT_HEALTH_CHECK_INTERVAL,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+/// JSON-RPC transport spreading the requests over several Starknet upstreams, in order of
+/// preference. The requests failing at the transport level, e.g. a refused connection, a
+/// timeout or a gateway error, are retried with an exponential backoff on the next healthy upstream. The
+/// responses which don't match the expected type are returned as is, without penalizing the
+/// upstream which sent them.
+///
+/// An upstream is ejected after [`FailoverConfig::max_failures`] consecutive failures or a failed
+/// health check, and restored by the first successful health check. The transactions aren't
+/// idempotent, so they are sent once to the preferred healthy upstream.
+#[derive(Debug, Clone)]
+pub struct FailoverTransport {
+    upstreams: Arc<[Upstream]>,
+    config: FailoverConfig,
+}
+
+#[derive(Debug)]
+struct Upstream {
+    /// Host of the upstream, used in the logs and metrics instead of its URL, which can
+    /// contain an API key.
+    host: String,
+    client: reqwest::Client,
+    url: Url,
+    failures: AtomicU32,
+    healthy: AtomicBool,
+}
+
+impl Upstream {
+    fn new(url: Url, config: &FailoverConfig) -> Self {
+        let host = url.host_str().unwrap_or_default().to_string();
+        STARKNET_UPSTREAM_HEALTHY.with_label_values(&[&host]).set(1);
+        let client = reqwest::Client::builder()
+            .connect_timeout(config.connect_timeout)
+            .timeout(config.request_timeout)
+            .build()
+            .expect("Failed to build the upstream HTTP client");
+        Self { host, client, url, failures: AtomicU32::new(0), healthy: AtomicBool::new(true) }
+    }
+
+    /// Sends the request to the upstream. The connection errors and the non-2xx statuses are
+    /// returned as [`HttpTransportError::Reqwest`], the responses which can't be decoded as
+    /// [`HttpTransportError::Json`].
+    async fn send_request<M, P, T>(&self, method: M, params: &P) -> Result<T, HttpTransportError>
+    where
+        M: Serialize,
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let request = JsonRpcRequest { id: 1, jsonrpc: "2.0", method, params };
+        let request = serde_json::to_string(&request).map_err(HttpTransportError::Json)?;
+        let response = self
+            .client
+            .post(self.url.clone())
+            .header("Content-Type", "application/json")
+            .body(request)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(HttpTransportError::Reqwest)?
+            .text()
+            .await
+            .map_err(HttpTransportError::Reqwest)?;
+        serde_json::from_str(&response).map_err(HttpTransportError::Json)
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn succeeded(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+        if !self.healthy.swap(true, Ordering::Relaxed) {
+            tracing::info!("Restored the Starknet upstream {}", self.host);
+            STARKNET_UPSTREAM_HEALTHY.with_label_values(&[&self.host]).set(1);
+        }
+    }
+
+    fn failed(&self, max_failures: u32) {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed).saturating_add(1);
+        if failures >= max_failures {
+            self.eject();
+        }
+    }
+
+    fn eject(&self) {
+        if self.healthy.swap(false, Ordering::Relaxed) {
+            tracing::warn!("Ejected the Starknet upstream {}", self.host);
+            STARKNET_UPSTREAM_HEALTHY.with_label_values(&[&self.host]).set(0);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonRpcRequest<'a, M, P> {
+    id: u64,
+    jsonrpc: &'static str,
+    method: M,
+    params: &'a P,
+}
+
+impl FailoverTransport {
+    /// Returns the transport over the primary upstream, followed by the fallback ones.
+    pub fn new(primary: Url, fallbacks: Vec<Url>, config: FailoverConfig) -> Self {
+        let upstreams = std::iter::once(primary).chain(fallbacks).map(|url| Upstream::new(url, &config)).collect();
+        Self { upstreams, config }
+    }
+
+    /// Returns the upstream of the attempt, rotating over the healthy upstreams, or over all
+    /// of them if none is healthy.
+    fn select(&self, attempt: u32) -> &Upstream {
+        let healthy = self.upstreams.iter().filter(|upstream| upstream.is_healthy()).collect::<Vec<_>>();
+        let candidates = if healthy.is_empty() { self.upstreams.iter().collect() } else { healthy };
+        candidates[attempt as usize % candidates.len()]
+    }
+
+    /// Sends the request to the upstreams, retrying it on the next healthy upstream if it is
+    /// idempotent and fails at the transport level.
+    async fn send<M, P, T>(&self, method: M, params: &P, idempotent: bool) -> Result<T, HttpTransportError>
+    where
+        M: Serialize + Copy,
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let max_retries = if idempotent { self.config.max_retries } else { 0 };
+        let mut backoff = self.config.initial_backoff;
+        let mut attempt = 0;
+        loop {
+            let upstream = self.select(attempt);
+            let error = match upstream.send_request(method, params).await {
+                Ok(response) => {
+                    upstream.succeeded();
+                    return Ok(response);
+                }
+                // The upstream answered, but not with the expected type: retrying won't help
+                Err(err @ HttpTransportError::Json(_)) => return Err(err),
+                Err(err) => err,
+            };
+            upstream.failed(self.config.max_failures);
+            if attempt >= max_retries {
+                return Err(error);
+            }
+
+            tracing::warn!(
+                "Starknet request to {} failed, retrying in {}ms: {error}",
+                upstream.host,
+                backoff.as_millis()
+            );
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+            attempt += 1;
+        }
+    }
+
+    /// Sends a request for a method outside of the Starknet specification, e.g.
+    /// `pathfinder_getProof`, and returns the raw JSON-RPC response. The method must be a read,
+    /// as it is retried like the other idempotent ones.
+    pub async fn send_raw_request(&self, method: &str, params: Value) -> Result<Value, HttpTransportError> {
+        self.send(method, &params, true).await
+    }
+
+    /// Returns the future checking the health of the upstreams every
+    /// [`FailoverConfig::health_check_interval`], until the transport is dropped.
+    pub fn health_check(&self) -> impl Future<Output = ()> + Send + 'static {
+        let upstreams = Arc::downgrade(&self.upstreams);
+        let period = self.config.health_check_interval;
+        async move {
+            let mut interval = tokio::time::interval(period);
+            // The first tick completes immediately, right after the upstreams were created.
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                let Some(upstreams) = upstreams.upgrade() else {
+                    return;
+                };
+                check_upstreams(&upstreams).await;
+            }
+        }
+    }
+}
+
+/// Requests the block number of each upstream, ejecting the ones which fail and restoring the
+/// ones which succeed.
+async fn check_upstreams(upstreams: &[Upstream]) {
+    let checks = upstreams.iter().map(|upstream| async move {
+        let response = upstream
+            .send_request::<_, _, JsonRpcResponse<u64>>(JsonRpcMethod::BlockNumber, &serde_json::json!([]))
+            .await;
+        match response {
+            Ok(JsonRpcResponse::Success { .. }) => upstream.succeeded(),
+            _ => upstream.eject(),
+        }
+    });
+    futures::future::join_all(checks).await;
+}
+
+/// Returns whether the method can be sent several times without side effects.
+const fn is_idempotent(method: JsonRpcMethod) -> bool {
+    !matches!(
+        method,
+        JsonRpcMethod::AddInvokeTransaction
+            | JsonRpcMethod::AddDeclareTransaction
+            | JsonRpcMethod::AddDeployAccountTransaction
+    )
+}
+
+#[async_trait]
+impl JsonRpcTransport for FailoverTransport {
+    type Error = HttpTransportError;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        self.send(method, &params, is_idempotent(method)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::net::SocketAddr;
+
+    use hyper::service::{make_service_fn, service_fn};
+    use hyper::{Body, Request, Response, Server, StatusCode};
+
+    use super::*;
+
+    const BLOCK_NUMBER: &str = r#"{"jsonrpc":"2.0","id":1,"result":42}"#;
+
+    /// Mock Starknet node answering with `body` if `up`, and with a `502 Bad Gateway`
+    /// otherwise. Returns its URL and its number of requests.
+    fn mock_upstream(up: Arc<AtomicBool>, body: &'static str) -> (Url, Arc<AtomicU32>) {
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+        let make_service = make_service_fn(move |_| {
+            let (up, counter) = (up.clone(), counter.clone());
+            async move {
+                Ok::<_, Infallible>(service_fn(move |_: Request<Body>| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    let response = if up.load(Ordering::Relaxed) {
+                        Response::new(Body::from(body))
+                    } else {
+                        Response::builder().status(StatusCode::BAD_GATEWAY).body(Body::from("Bad Gateway")).unwrap()
+                    };
+                    async move { Ok::<_, Infallible>(response) }
+                }))
+            }
+        });
+        let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+        let url = Url::parse(&format!("http://{}", server.local_addr())).unwrap();
+        tokio::spawn(server);
+        (url, requests)
+    }
+
+    /// Mock Starknet node accepting the connections but never answering. Returns its URL.
+    fn hung_upstream() -> Url {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        std::thread::spawn(move || {
+            // The connections are kept open, without reading nor answering the requests
+            let _connections = listener.incoming().collect::<Vec<_>>();
+        });
+        url
+    }
+
+    fn config() -> FailoverConfig {
+        FailoverConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_failures: 1,
+            request_timeout: Duration::from_millis(200),
+            ..Default::default()
+        }
+    }
+
+    async fn block_number(transport: &FailoverTransport, method: JsonRpcMethod) -> Option<u64> {
+        match transport.send_request(method, serde_json::json!([])).await {
+            Ok(JsonRpcResponse::Success { result, .. }) => Some(result),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failover() {
+        // Given
+        let (primary, primary_requests) = mock_upstream(Arc::new(AtomicBool::new(false)), BLOCK_NUMBER);
+        let (fallback, fallback_requests) = mock_upstream(Arc::new(AtomicBool::new(true)), BLOCK_NUMBER);
+        let transport = FailoverTransport::new(primary, vec![fallback], config());
+
+        // When
+        let first = block_number(&transport, JsonRpcMethod::BlockNumber).await;
+        let second = block_number(&transport, JsonRpcMethod::BlockNumber).await;
+
+        // Then
+        // The primary was ejected after its first failure
+        assert_eq!((first, second), (Some(42), Some(42)));
+        assert_eq!(primary_requests.load(Ordering::Relaxed), 1);
+        assert_eq!(fallback_requests.load(Ordering::Relaxed), 2);
+    }
+
+    #[tokio::test]
+    async fn test_transactions_are_not_retried() {
+        // Given
+        let (primary, primary_requests) = mock_upstream(Arc::new(AtomicBool::new(false)), BLOCK_NUMBER);
+        let (fallback, fallback_requests) = mock_upstream(Arc::new(AtomicBool::new(true)), BLOCK_NUMBER);
+        let transport = FailoverTransport::new(primary, vec![fallback], config());
+
+        // When
+        let result = block_number(&transport, JsonRpcMethod::AddInvokeTransaction).await;
+
+        // Then
+        assert_eq!(result, None);
+        assert_eq!(primary_requests.load(Ordering::Relaxed), 1);
+        assert_eq!(fallback_requests.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_unexpected_responses_are_not_retried() {
+        // Given
+        let (primary, primary_requests) =
+            mock_upstream(Arc::new(AtomicBool::new(true)), r#"{"jsonrpc":"2.0","id":1,"result":"latest"}"#);
+        let (fallback, fallback_requests) = mock_upstream(Arc::new(AtomicBool::new(true)), BLOCK_NUMBER);
+        let transport = FailoverTransport::new(primary, vec![fallback], config());
+
+        // When
+        let result = transport.send_request::<_, u64>(JsonRpcMethod::BlockNumber, serde_json::json!([])).await;
+
+        // Then
+        // The decoding error was returned without penalizing the primary
+        assert!(matches!(result, Err(HttpTransportError::Json(_))));
+        assert_eq!(primary_requests.load(Ordering::Relaxed), 1);
+        assert_eq!(fallback_requests.load(Ordering::Relaxed), 0);
+        assert!(transport.upstreams[0].is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_hung_upstream() {
+        // Given
+        let primary = hung_upstream();
+        let (fallback, fallback_requests) = mock_upstream(Arc::new(AtomicBool::new(true)), BLOCK_NUMBER);
+        let transport = FailoverTransport::new(primary, vec![fallback], config());
+
+        // When
+        let result = block_number(&transport, JsonRpcMethod::BlockNumber).await;
+
+        // Then
+        // The timed out request failed over, and the primary was ejected
+        assert_eq!(result, Some(42));
+        assert_eq!(fallback_requests.load(Ordering::Relaxed), 1);
+        assert!(!transport.upstreams[0].is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_health_check_hung_upstream() {
+        // Given
+        let primary = hung_upstream();
+        let (fallback, _) = mock_upstream(Arc::new(AtomicBool::new(true)), BLOCK_NUMBER);
+        let transport = FailoverTransport::new(primary, vec![fallback], config());
+
+        // When
+        let checked = tokio::time::timeout(Duration::from_secs(5), check_upstreams(&transport.upstreams)).await;
+
+        // Then
+        // The health check doesn't wait for the hung upstream
+        assert!(checked.is_ok());
+        assert!(!transport.upstreams[0].is_healthy());
+        assert!(transport.upstreams[1].is_healthy());
+    }
+
+    #[tokio::test]
+    async fn test_health_check() {
+        // Given
+        let primary_up = Arc::new(AtomicBool::new(false));
+        let (primary, _) = mock_upstream(primary_up.clone(), BLOCK_NUMBER);
+        let (fallback, _) = mock_upstream(Arc::new(AtomicBool::new(true)), BLOCK_NUMBER);
+        let transport = FailoverTransport::new(primary, vec![fallback], config());
+
+        // When
+        check_upstreams(&transport.upstreams).await;
+        let ejected = transport.upstreams[0].is_healthy();
+        primary_up.store(true, Ordering::Relaxed);
+        check_upstreams(&transport.upstreams).await;
+        let restored = transport.upstreams[0].is_healthy();
+
+        // Then
+        assert!(!ejected);
+        assert!(restored);
+        assert!(transport.upstreams[1].is_healthy());
+    }
+}
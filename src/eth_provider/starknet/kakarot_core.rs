@@ -1,9 +1,6 @@
-use std::str::FromStr;
-
 use crate::models::felt::Felt252Wrapper;
 use alloy_rlp::Encodable;
 use cainome::rs::abigen_legacy;
-use dotenv::dotenv;
 use lazy_static::lazy_static;
 use reth_primitives::{Address, Transaction, TransactionSigned};
 use starknet::{
@@ -35,41 +32,56 @@ pub mod core {
     abigen_legacy!(KakarotCore, "./.kakarot/artifacts/kakarot.json");
 }
 
-fn env_var_to_field_element(var_name: &str) -> FieldElement {
-    dotenv().ok();
-    let env_var = std::env::var(var_name).unwrap_or_else(|_| panic!("Missing environment variable {var_name}"));
-
-    FieldElement::from_str(&env_var).unwrap_or_else(|_| panic!("Invalid hex string for {var_name}"))
-}
-
 lazy_static! {
-    // Contract addresses
-    pub static ref KAKAROT_ADDRESS: FieldElement = env_var_to_field_element("KAKAROT_ADDRESS");
-
-    // Contract class hashes
-    pub static ref PROXY_ACCOUNT_CLASS_HASH: FieldElement = env_var_to_field_element("PROXY_ACCOUNT_CLASS_HASH");
-    pub static ref EXTERNALLY_OWNED_ACCOUNT_CLASS_HASH: FieldElement =
-        env_var_to_field_element("EXTERNALLY_OWNED_ACCOUNT_CLASS_HASH");
-    pub static ref CONTRACT_ACCOUNT_CLASS_HASH: FieldElement = env_var_to_field_element("CONTRACT_ACCOUNT_CLASS_HASH");
-
     // Contract selectors
     pub static ref ETH_SEND_TRANSACTION: FieldElement = selector!("eth_send_transaction");
 }
 
+/// The Kakarot contract address and the account class hashes of a Kakarot
+/// deployment on the underlying Starknet chain.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KakarotDeployment {
+    /// Kakarot contract address.
+    pub kakarot_address: FieldElement,
+    /// Proxy account class hash.
+    pub proxy_account_class_hash: FieldElement,
+    /// Eoa class hash.
+    pub externally_owned_account_class_hash: FieldElement,
+    /// Contract Account class hash.
+    pub contract_account_class_hash: FieldElement,
+}
+
+impl KakarotDeployment {
+    pub const fn new(
+        kakarot_address: FieldElement,
+        proxy_account_class_hash: FieldElement,
+        externally_owned_account_class_hash: FieldElement,
+        contract_account_class_hash: FieldElement,
+    ) -> Self {
+        Self {
+            kakarot_address,
+            proxy_account_class_hash,
+            externally_owned_account_class_hash,
+            contract_account_class_hash,
+        }
+    }
+}
+
 // Kakarot utils
 /// Compute the starknet address given a eth address
-pub fn starknet_address(address: Address) -> FieldElement {
-    get_contract_address(into_via_wrapper!(address), *PROXY_ACCOUNT_CLASS_HASH, &[], *KAKAROT_ADDRESS)
+pub fn starknet_address(kakarot: &KakarotDeployment, address: Address) -> FieldElement {
+    get_contract_address(into_via_wrapper!(address), kakarot.proxy_account_class_hash, &[], kakarot.kakarot_address)
 }
 
 /// Convert a Ethereum transaction into a Starknet transaction
 pub fn to_starknet_transaction(
+    kakarot: &KakarotDeployment,
     transaction: &TransactionSigned,
     chain_id: u64,
     signer: Address,
     max_fee: u64,
 ) -> EthProviderResult<BroadcastedInvokeTransactionV1> {
-    let starknet_address = starknet_address(signer);
+    let starknet_address = starknet_address(kakarot, signer);
 
     let nonce = FieldElement::from(transaction.nonce());
 
@@ -104,7 +116,7 @@ pub fn to_starknet_transaction(
     let mut execute_calldata = Vec::with_capacity(capacity);
     execute_calldata.append(&mut vec![
         FieldElement::ONE,                     // call array length
-        *KAKAROT_ADDRESS,                      // contract address
+        kakarot.kakarot_address,               // contract address
         *ETH_SEND_TRANSACTION,                 // selector
         FieldElement::ZERO,                    // data offset
         FieldElement::from(signed_data.len()), // data length
@@ -121,3 +133,28 @@ pub fn to_starknet_transaction(
         is_query: false,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_starknet_address_depends_on_deployment() {
+        // Given
+        let deployment =
+            KakarotDeployment::new(FieldElement::ONE, FieldElement::TWO, FieldElement::ZERO, FieldElement::ZERO);
+        let other_deployment = KakarotDeployment { kakarot_address: FieldElement::THREE, ..deployment };
+        let address = Address::with_last_byte(1);
+
+        // When
+        let starknet_address_1 = starknet_address(&deployment, address);
+        let starknet_address_2 = starknet_address(&other_deployment, address);
+
+        // Then
+        assert_eq!(
+            starknet_address_1,
+            get_contract_address(FieldElement::ONE, FieldElement::TWO, &[], FieldElement::ONE)
+        );
+        assert_ne!(starknet_address_1, starknet_address_2);
+    }
+}
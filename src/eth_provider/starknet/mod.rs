@@ -1,5 +1,8 @@
 #![allow(non_snake_case)]
+pub mod failover;
 pub mod kakarot_core;
+pub mod proof;
+pub mod transport;
 
 use cainome::rs::abigen_legacy;
 use lazy_static::lazy_static;
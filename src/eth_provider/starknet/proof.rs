@@ -0,0 +1,219 @@
+use reth_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use serde_with::serde_as;
+use starknet::core::serde::unsigned_field_element::UfeHex;
+use starknet::core::types::{BlockId, BlockTag};
+use starknet_crypto::FieldElement;
+
+use crate::eth_provider::error::EthProviderError;
+use crate::eth_provider::provider::EthProviderResult;
+use crate::eth_provider::starknet::failover::FailoverTransport;
+
+/// Tag prefixing the encoding of a binary node.
+pub const BINARY_NODE_TAG: u8 = 0;
+/// Tag prefixing the encoding of an edge node.
+pub const EDGE_NODE_TAG: u8 = 1;
+
+/// Output of the `pathfinder_getProof` method.
+/// See <https://github.com/eqlabs/pathfinder/blob/main/doc/rpc/pathfinder_rpc_api.json>
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetProofOutput {
+    /// Commitment of the global state. Missing for blocks before Starknet v0.11.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub state_commitment: Option<FieldElement>,
+    /// Root of the classes trie. Missing for blocks before Starknet v0.11.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default)]
+    pub class_commitment: Option<FieldElement>,
+    /// Membership proof of the contract in the contracts trie.
+    pub contract_proof: Vec<ProofNode>,
+    /// Contract data, missing if the contract is not deployed.
+    pub contract_data: Option<ContractData>,
+}
+
+/// Data of a contract, as returned by `pathfinder_getProof`.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractData {
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub nonce: FieldElement,
+    /// Root of the contract storage trie.
+    #[serde_as(as = "UfeHex")]
+    pub root: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub contract_state_hash_version: FieldElement,
+    /// Membership proofs of the requested keys in the contract storage trie, in the same order as the keys.
+    pub storage_proofs: Vec<Vec<ProofNode>>,
+}
+
+/// Node of a Starknet Merkle-Patricia trie proof.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofNode {
+    #[serde(alias = "Binary")]
+    Binary(BinaryNode),
+    #[serde(alias = "Edge")]
+    Edge(EdgeNode),
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinaryNode {
+    #[serde_as(as = "UfeHex")]
+    pub left: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub right: FieldElement,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeNode {
+    #[serde_as(as = "UfeHex")]
+    pub child: FieldElement,
+    pub path: EdgePath,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgePath {
+    #[serde_as(as = "UfeHex")]
+    pub value: FieldElement,
+    pub len: u8,
+}
+
+impl ProofNode {
+    /// Encodes the node as `BINARY_NODE_TAG || left || right` for a binary node
+    /// and `EDGE_NODE_TAG || child || path || path length` for an edge node.
+    /// All the field elements are encoded on 32 bytes, big endian.
+    pub fn encode(&self) -> Bytes {
+        let mut encoded = Vec::with_capacity(66);
+        match self {
+            Self::Binary(node) => {
+                encoded.push(BINARY_NODE_TAG);
+                encoded.extend_from_slice(&node.left.to_bytes_be());
+                encoded.extend_from_slice(&node.right.to_bytes_be());
+            }
+            Self::Edge(node) => {
+                encoded.push(EDGE_NODE_TAG);
+                encoded.extend_from_slice(&node.child.to_bytes_be());
+                encoded.extend_from_slice(&node.path.value.to_bytes_be());
+                encoded.push(node.path.len);
+            }
+        }
+        encoded.into()
+    }
+}
+
+/// Client for the Starknet storage proofs, served by Pathfinder through `pathfinder_getProof`.
+/// The Starknet provider trait does not expose this method, hence the raw JSON-RPC calls, sent
+/// through the upstreams of the Starknet provider.
+#[derive(Debug, Clone)]
+pub struct StarknetProofClient {
+    transport: FailoverTransport,
+}
+
+impl StarknetProofClient {
+    pub const fn new(transport: FailoverTransport) -> Self {
+        Self { transport }
+    }
+
+    /// Returns the proof of the contract at `contract_address` in the global state and
+    /// the proofs of the given storage `keys` in the contract storage. Proofs are only
+    /// available for sealed blocks, not for the pending one.
+    pub async fn get_proof(
+        &self,
+        block_id: BlockId,
+        contract_address: FieldElement,
+        keys: &[FieldElement],
+    ) -> EthProviderResult<GetProofOutput> {
+        let params = json!({
+            "block_id": block_id_to_json(block_id),
+            "contract_address": contract_address,
+            "keys": keys,
+        });
+
+        let mut response =
+            self.transport.send_raw_request("pathfinder_getProof", params).await.map_err(eyre::Error::from)?;
+
+        if let Some(error) = response.get("error") {
+            return Err(EthProviderError::Other(eyre::eyre!("pathfinder_getProof failed: {error}")));
+        }
+        let result = response.get_mut("result").map(Value::take).unwrap_or_default();
+        Ok(serde_json::from_value(result).map_err(eyre::Error::from)?)
+    }
+}
+
+/// Converts the block id to its JSON-RPC representation.
+fn block_id_to_json(block_id: BlockId) -> Value {
+    match block_id {
+        BlockId::Hash(hash) => json!({ "block_hash": hash }),
+        BlockId::Number(number) => json!({ "block_number": number }),
+        BlockId::Tag(BlockTag::Latest) => json!("latest"),
+        BlockId::Tag(BlockTag::Pending) => json!("pending"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_get_proof_output() {
+        // Given
+        let output = json!({
+            "state_commitment": "0x1",
+            "class_commitment": "0x2",
+            "contract_proof": [
+                {"binary": {"left": "0x3", "right": "0x4"}},
+                {"edge": {"child": "0x5", "path": {"value": "0x6", "len": 249}}},
+            ],
+            "contract_data": {
+                "class_hash": "0x7",
+                "nonce": "0x0",
+                "root": "0x8",
+                "contract_state_hash_version": "0x0",
+                "storage_proofs": [[{"edge": {"child": "0x9", "path": {"value": "0xa", "len": 251}}}]],
+            },
+        });
+
+        // When
+        let output: GetProofOutput = serde_json::from_value(output).unwrap();
+
+        // Then
+        assert_eq!(output.state_commitment, Some(FieldElement::ONE));
+        assert_eq!(
+            output.contract_proof[0],
+            ProofNode::Binary(BinaryNode { left: FieldElement::from(3u8), right: FieldElement::from(4u8) })
+        );
+        let contract_data = output.contract_data.unwrap();
+        assert_eq!(contract_data.root, FieldElement::from(8u8));
+        assert_eq!(contract_data.storage_proofs.len(), 1);
+    }
+
+    #[test]
+    fn test_encode_proof_node() {
+        // Given
+        let binary = ProofNode::Binary(BinaryNode { left: FieldElement::ONE, right: FieldElement::TWO });
+        let edge =
+            ProofNode::Edge(EdgeNode { child: FieldElement::ONE, path: EdgePath { value: FieldElement::TWO, len: 3 } });
+
+        // When
+        let binary = binary.encode();
+        let edge = edge.encode();
+
+        // Then
+        assert_eq!(binary.len(), 65);
+        assert_eq!(binary[0], BINARY_NODE_TAG);
+        assert_eq!(binary[32], 1);
+        assert_eq!(binary[64], 2);
+        assert_eq!(edge.len(), 66);
+        assert_eq!(edge[0], EDGE_NODE_TAG);
+        assert_eq!(edge[64], 2);
+        assert_eq!(edge[65], 3);
+    }
+}
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Serialize};
+use starknet::providers::jsonrpc::{JsonRpcMethod, JsonRpcResponse, JsonRpcTransport};
+use tracing::Instrument;
+
+use crate::metrics::STARKNET_REQUEST_DURATION;
+
+/// JSON-RPC transport recording the latency of the requests sent to the Starknet provider,
+/// and tracing each of them in a `starknet_request` span.
+#[derive(Debug, Clone)]
+pub struct InstrumentedTransport<T> {
+    inner: T,
+}
+
+impl<T> InstrumentedTransport<T> {
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl<T> JsonRpcTransport for InstrumentedTransport<T>
+where
+    T: JsonRpcTransport + Send + Sync,
+{
+    type Error = T::Error;
+
+    async fn send_request<P, R>(&self, method: JsonRpcMethod, params: P) -> Result<JsonRpcResponse<R>, Self::Error>
+    where
+        P: Serialize + Send + Sync,
+        R: DeserializeOwned,
+    {
+        // The method serializes to its JSON-RPC name, e.g. `starknet_call`.
+        let method_name = serde_json::to_value(method)
+            .ok()
+            .and_then(|name| name.as_str().map(ToString::to_string))
+            .unwrap_or_default();
+        let _timer = STARKNET_REQUEST_DURATION.with_label_values(&[&method_name]).start_timer();
+        let span = tracing::info_span!("starknet_request", method = %method_name);
+        self.inner.send_request(method, params).instrument(span).await
+    }
+}
@@ -4,11 +4,14 @@ use cainome::cairo_serde::Error;
 use itertools::Itertools;
 use mongodb::bson::{doc, Document};
 use reth_primitives::{U128, U256};
+use reth_rpc_types::TransactionReceipt;
 use starknet::{
     core::types::{ContractErrorData, StarknetError},
     providers::ProviderError,
 };
 
+use super::error::EthProviderError;
+
 /// Converts an iterator of `Into<D>` into a `Vec<D>`.
 pub(crate) fn iter_into<D, S: Into<D>>(iter: impl IntoIterator<Item = S>) -> Vec<D> {
     iter.into_iter().map_into().collect()
@@ -48,6 +51,51 @@ pub fn split_u256<T: From<u128>>(value: impl Into<U256>) -> [T; 2] {
     [T::from(low), T::from(high)]
 }
 
+/// Checks that the reward percentiles are in the [0, 100] range and monotonically increasing.
+pub(crate) fn validate_reward_percentiles(percentiles: &[f64]) -> Result<(), EthProviderError> {
+    let mut previous = None;
+    for &percentile in percentiles {
+        if !(0.0..=100.0).contains(&percentile) || previous.is_some_and(|previous| percentile <= previous) {
+            return Err(EthProviderError::InvalidParams(format!("invalid reward percentile: {percentile}")));
+        }
+        previous = Some(percentile);
+    }
+    Ok(())
+}
+
+/// Returns the effective priority fee per gas paid by the transaction and the gas it used.
+pub(crate) fn effective_tip(receipt: &TransactionReceipt, base_fee: U256) -> (U256, u64) {
+    let tip = U256::from(receipt.effective_gas_price).saturating_sub(base_fee);
+    let gas_used = receipt.gas_used.unwrap_or_default().try_into().unwrap_or(u64::MAX);
+    (tip, gas_used)
+}
+
+/// Computes the rewards of a block for the given percentiles, from the
+/// effective priority fees of its transactions weighted by the gas they used.
+/// Follows geth's implementation: <https://github.com/ethereum/go-ethereum/blob/master/eth/gasprice/feehistory.go>
+pub(crate) fn rewards_for_percentiles(mut tips: Vec<(U256, u64)>, percentiles: &[f64]) -> Vec<U256> {
+    if tips.is_empty() {
+        return vec![U256::ZERO; percentiles.len()];
+    }
+
+    tips.sort_unstable_by_key(|(tip, _)| *tip);
+    let total_gas_used = tips.iter().fold(0u64, |acc, (_, gas_used)| acc.saturating_add(*gas_used));
+
+    let mut index = 0;
+    let mut cumulative_gas_used = tips[0].1;
+    percentiles
+        .iter()
+        .map(|percentile| {
+            let threshold = (total_gas_used as f64 * percentile / 100.) as u64;
+            while cumulative_gas_used < threshold && index < tips.len() - 1 {
+                index += 1;
+                cumulative_gas_used = cumulative_gas_used.saturating_add(tips[index].1);
+            }
+            tips[index].0
+        })
+        .collect()
+}
+
 pub(crate) const fn contract_not_found<T>(err: &Result<T, Error>) -> bool {
     match err {
         Ok(_) => false,
@@ -66,3 +114,39 @@ pub(crate) fn entrypoint_not_found<T>(err: &Result<T, Error>) -> bool {
         ),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_reward_percentiles() {
+        assert!(validate_reward_percentiles(&[]).is_ok());
+        assert!(validate_reward_percentiles(&[0., 25., 50.5, 100.]).is_ok());
+        assert!(validate_reward_percentiles(&[50., 25.]).is_err());
+        assert!(validate_reward_percentiles(&[25., 25.]).is_err());
+        assert!(validate_reward_percentiles(&[-1.]).is_err());
+        assert!(validate_reward_percentiles(&[100.1]).is_err());
+        assert!(validate_reward_percentiles(&[f64::NAN]).is_err());
+    }
+
+    #[test]
+    fn test_rewards_for_percentiles() {
+        // Given
+        let tips = vec![(U256::from(3), 10), (U256::from(1), 70), (U256::from(2), 20)];
+
+        // When
+        let rewards = rewards_for_percentiles(tips, &[0., 50., 70., 80., 100.]);
+
+        // Then
+        // The transaction paying 1 wei of tip uses 70% of the gas, the one paying 2 wei
+        // uses 20% and the one paying 3 wei uses 10%.
+        let expected = [1, 1, 1, 2, 3].into_iter().map(U256::from).collect::<Vec<_>>();
+        assert_eq!(rewards, expected);
+    }
+
+    #[test]
+    fn test_rewards_for_percentiles_empty_block() {
+        assert_eq!(rewards_for_percentiles(vec![], &[10., 90.]), vec![U256::ZERO; 2]);
+    }
+}
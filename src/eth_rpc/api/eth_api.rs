@@ -206,7 +206,7 @@ pub trait EthApi {
     async fn sign_transaction(&self, transaction: TransactionRequest) -> Result<Bytes>;
 
     /// Signs data via [EIP-712](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-712.md).
-    #[method(name = "signTypedData")]
+    #[method(name = "signTypedData", aliases = ["eth_signTypedData_v4"])]
     async fn sign_typed_data(&self, address: Address, data: serde_json::Value) -> Result<Bytes>;
 
     /// Returns the account and storage values of the specified account including the Merkle-proof.
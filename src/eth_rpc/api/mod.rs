@@ -1,4 +1,5 @@
 pub mod alchemy_api;
 pub mod eth_api;
 pub mod net_api;
+pub mod rpc_api;
 pub mod web3_api;
@@ -3,6 +3,8 @@ use jsonrpsee::proc_macros::rpc;
 use reth_primitives::U64;
 use reth_rpc_types::PeerCount;
 
+use crate::models::health::HealthReport;
+
 // TODO: Define and implement of methods of Net API
 #[rpc(server, namespace = "net")]
 #[async_trait]
@@ -20,8 +22,8 @@ pub trait NetApi {
     #[method(name = "listening")]
     fn listening(&self) -> Result<bool>;
 
-    /// Returns true if Kakarot RPC_URL is reachable.
-    /// Otherwise throw an EthApiError.
+    /// Returns the health of the Mongo database, of the Starknet provider and the lag of
+    /// the indexer. The RPC is healthy if all of them are.
     #[method(name = "health")]
-    async fn health(&self) -> Result<bool>;
+    async fn health(&self) -> Result<HealthReport>;
 }
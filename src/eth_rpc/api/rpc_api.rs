@@ -0,0 +1,12 @@
+use std::collections::BTreeMap;
+
+use jsonrpsee::core::RpcResult as Result;
+use jsonrpsee::proc_macros::rpc;
+
+#[rpc(server, namespace = "rpc")]
+#[async_trait]
+pub trait RpcApi {
+    /// Returns the enabled RPC namespaces with their version
+    #[method(name = "modules")]
+    fn modules(&self) -> Result<BTreeMap<String, String>>;
+}
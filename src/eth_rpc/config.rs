@@ -1,17 +1,156 @@
-use eyre::{eyre, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Default maximum number of connections of the RPC server.
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 100;
+
+/// Default maximum number of requests in a JSON-RPC batch.
+pub const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
+
+/// Default maximum size of a request body, in bytes.
+pub const DEFAULT_MAX_REQUEST_SIZE: u32 = 15 * 1024 * 1024;
+
+/// Default maximum size of a response body, in bytes.
+pub const DEFAULT_MAX_RESPONSE_SIZE: u32 = 100 * 1024 * 1024;
+
+/// Default duration after which a method call is cancelled.
+pub const DEFAULT_METHOD_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default weights of the methods in the rate limiting, the other methods weigh 1.
+pub const DEFAULT_METHOD_WEIGHTS: [(&str, u32); 5] = [
+    ("eth_getLogs", 10),
+    ("eth_call", 5),
+    ("eth_estimateGas", 5),
+    ("eth_getProof", 5),
+    ("alchemy_getTokenBalances", 5),
+];
 
 pub struct RPCConfig {
     pub socket_addr: String,
+    pub max_connections: u32,
+    pub rate_limit: Option<RateLimitConfig>,
+    /// TOML file of the clients allowed to call the RPC, authentication is disabled if `None`.
+    pub auth_keys_file: Option<PathBuf>,
+    /// Maximum number of requests in a batch, batches are rejected if 0.
+    pub max_batch_size: u32,
+    /// Maximum size of a request body, in bytes.
+    pub max_request_size: u32,
+    /// Maximum size of a response body, in bytes.
+    pub max_response_size: u32,
+    pub timeouts: TimeoutConfig,
+}
+
+/// Durations after which the method calls are cancelled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeoutConfig {
+    /// Timeout of the methods without a specific one.
+    pub default: Duration,
+    /// Timeouts of specific methods.
+    pub methods: HashMap<String, Duration>,
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        Self { default: DEFAULT_METHOD_TIMEOUT, methods: HashMap::new() }
+    }
+}
+
+impl TimeoutConfig {
+    /// Returns the timeout of the method.
+    pub fn timeout(&self, method: &str) -> Duration {
+        self.methods.get(method).copied().unwrap_or(self.default)
+    }
+}
+
+/// Token bucket rate limiting of the clients, identified by their API key or IP address.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimitConfig {
+    /// Weight refilled in the bucket of each client per second.
+    pub per_second: u32,
+    /// Capacity of the bucket of each client, i.e. the weight of a burst of requests.
+    pub burst: u32,
+    /// Weight of the methods, the other methods weigh 1.
+    pub method_weights: HashMap<String, u32>,
+    /// Identifies the clients with the first address of the `X-Forwarded-For` header, when
+    /// the RPC runs behind a trusted proxy.
+    pub trust_forwarded_for: bool,
+}
+
+impl RateLimitConfig {
+    /// Returns a configuration with a burst of `per_second` and the default method weights.
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            per_second,
+            burst: per_second,
+            method_weights: DEFAULT_METHOD_WEIGHTS
+                .iter()
+                .map(|(method, weight)| ((*method).to_string(), *weight))
+                .collect(),
+            trust_forwarded_for: false,
+        }
+    }
+
+    /// Returns the weight of the method.
+    pub fn weight(&self, method: &str) -> u32 {
+        self.method_weights.get(method).copied().unwrap_or(1)
+    }
 }
 
 impl RPCConfig {
-    pub const fn new(socket_addr: String) -> Self {
-        Self { socket_addr }
+    pub fn new(socket_addr: String) -> Self {
+        Self {
+            socket_addr,
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            rate_limit: None,
+            auth_keys_file: None,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            timeouts: TimeoutConfig::default(),
+        }
+    }
+
+    /// Sets the maximum number of connections of the RPC server.
+    #[must_use]
+    pub const fn with_max_connections(mut self, max_connections: u32) -> Self {
+        self.max_connections = max_connections;
+        self
+    }
+
+    /// Sets the maximum number of requests in a batch, 0 rejecting the batches.
+    #[must_use]
+    pub const fn with_max_batch_size(mut self, max_batch_size: u32) -> Self {
+        self.max_batch_size = max_batch_size;
+        self
+    }
+
+    /// Sets the maximum sizes of the request and response bodies, in bytes.
+    #[must_use]
+    pub const fn with_max_body_sizes(mut self, max_request_size: u32, max_response_size: u32) -> Self {
+        self.max_request_size = max_request_size;
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Sets the timeouts of the method calls.
+    #[must_use]
+    pub fn with_timeouts(mut self, timeouts: TimeoutConfig) -> Self {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// Rate limits the clients of the RPC server.
+    #[must_use]
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
     }
 
-    pub fn from_env() -> Result<Self> {
-        let socket_addr = std::env::var("KAKAROT_RPC_URL")
-            .map_err(|_| eyre!("Missing mandatory environment variable: KAKAROT_RPC_URL"))?;
-        Ok(Self::new(socket_addr))
+    /// Authenticates the clients of the RPC server with the keys file.
+    #[must_use]
+    pub fn with_auth_keys_file(mut self, auth_keys_file: PathBuf) -> Self {
+        self.auth_keys_file = Some(auth_keys_file);
+        self
     }
 }
@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock, Weak};
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::future::{self, BoxFuture, Either, FutureExt, Ready};
+use hyper::header::AUTHORIZATION;
+use hyper::{Body, Method, Request, Response, StatusCode};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::MethodResponse;
+use jsonwebtoken::{Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use thiserror::Error;
+use tower::{Layer, Service};
+
+use super::metrics::METRICS_PATH;
+use super::readiness::READY_PATH;
+use crate::eth_provider::error::{rpc_err, EthRpcErrorCode};
+use crate::eth_rpc::rpc::{KakarotRpcModule, HEALTH_METHOD};
+use crate::eth_rpc::HEALTH_PATH;
+
+/// Header of the API key identifying the client.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Interval at which the keys file is reloaded.
+pub const KEYS_RELOAD_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Maximum difference, in seconds, between the `iat` claim of a JWT and the current time,
+/// as on the authenticated endpoint of the execution clients.
+const JWT_MAX_IAT_DIFF: u64 = 60;
+
+/// Namespace of the methods every client can call.
+const RPC_NAMESPACE: &str = "rpc";
+
+tokio::task_local! {
+    /// The authenticated client of the HTTP request being processed.
+    static AUTHENTICATED_CLIENT: Arc<Client>;
+}
+
+#[derive(Debug, Error)]
+pub enum AuthError {
+    #[error("failed to read the keys file {}: {}", .0.display(), .1)]
+    Io(PathBuf, #[source] std::io::Error),
+    #[error("invalid keys file {}: {}", .0.display(), .1)]
+    InvalidKeysFile(PathBuf, String),
+}
+
+/// A client allowed to call the RPC, authenticated by an API key or a JWT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Client {
+    pub name: String,
+    /// The namespaces the client can call, every served namespace if `None`.
+    pub namespaces: Option<Vec<KakarotRpcModule>>,
+}
+
+impl Client {
+    /// Returns whether the client can call the method. The `rpc` namespace and the health
+    /// method are always allowed.
+    pub fn is_allowed(&self, method: &str) -> bool {
+        let Some(namespaces) = &self.namespaces else {
+            return true;
+        };
+        if method == HEALTH_METHOD {
+            return true;
+        }
+        let namespace = method.split_once('_').map_or(method, |(namespace, _)| namespace);
+        namespace == RPC_NAMESPACE || namespaces.iter().any(|module| module.namespace() == namespace)
+    }
+}
+
+/// The keys file, listing the clients as `[[clients]]` tables.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    #[serde(default)]
+    clients: Vec<ClientEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ClientEntry {
+    name: String,
+    /// API key sent in the `X-Api-Key` header or as the request path.
+    api_key: Option<String>,
+    /// Hex encoded 32 bytes secret of the HS256 JWTs sent in the `Authorization` header.
+    jwt_secret: Option<String>,
+    namespaces: Option<Vec<String>>,
+}
+
+/// Claims of the JWTs, only the issuance time is required.
+#[derive(Deserialize)]
+struct Claims {
+    iat: u64,
+}
+
+/// The clients of the keys file, by credential.
+#[derive(Default)]
+struct Clients {
+    api_keys: HashMap<String, Arc<Client>>,
+    jwt_secrets: Vec<(DecodingKey, Arc<Client>)>,
+}
+
+impl FromStr for Clients {
+    type Err = String;
+
+    fn from_str(content: &str) -> Result<Self, Self::Err> {
+        let keys_file: KeysFile = toml::from_str(content).map_err(|err| err.to_string())?;
+
+        let mut clients = Self::default();
+        for entry in keys_file.clients {
+            let name = entry.name;
+            let namespaces = entry
+                .namespaces
+                .map(|namespaces| {
+                    namespaces
+                        .iter()
+                        .map(|namespace| KakarotRpcModule::from_str(namespace))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()
+                .map_err(|err| format!("client {name}: {err}"))?;
+            let client = Arc::new(Client { name: name.clone(), namespaces });
+
+            match (entry.api_key, entry.jwt_secret) {
+                (Some(api_key), None) if !api_key.is_empty() && !api_key.contains('/') => {
+                    if clients.api_keys.insert(api_key, client).is_some() {
+                        return Err(format!("client {name}: duplicated API key"));
+                    }
+                }
+                (Some(_), None) => return Err(format!("client {name}: API keys must be non empty and without '/'")),
+                (None, Some(jwt_secret)) => {
+                    let secret = hex::decode(jwt_secret.trim().trim_start_matches("0x"))
+                        .ok()
+                        .filter(|secret| secret.len() == 32)
+                        .ok_or_else(|| format!("client {name}: the JWT secret must be 32 hex encoded bytes"))?;
+                    clients.jwt_secrets.push((DecodingKey::from_secret(&secret), client));
+                }
+                _ => return Err(format!("client {name}: expected either an api_key or a jwt_secret")),
+            }
+        }
+        Ok(clients)
+    }
+}
+
+impl Clients {
+    /// Returns the client authenticated by the request, along with whether it was authenticated
+    /// by the API key in its path, or the reason of the rejection.
+    ///
+    /// The credentials are read from the `Authorization: Bearer <JWT>` header, then from the
+    /// `X-Api-Key` header, then from the path, e.g. `/<API key>`.
+    fn authenticate(&self, request: &Request<Body>) -> Result<(Arc<Client>, bool), &'static str> {
+        let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+
+        if let Some(authorization) = header(AUTHORIZATION.as_str()) {
+            let token = authorization.strip_prefix("Bearer ").ok_or("Invalid authorization header, expected a JWT")?;
+            return self.verify_jwt(token).map(|client| (client, false)).ok_or("Invalid JWT");
+        }
+        if let Some(api_key) = header(API_KEY_HEADER) {
+            return self.api_keys.get(api_key).map(|client| (client.clone(), false)).ok_or("Invalid API key");
+        }
+        match request.uri().path().strip_prefix('/').filter(|api_key| !api_key.is_empty() && !api_key.contains('/')) {
+            Some(api_key) => self.api_keys.get(api_key).map(|client| (client.clone(), true)).ok_or("Invalid API key"),
+            None => Err("Missing API key or JWT"),
+        }
+    }
+
+    /// Returns the client whose secret signed the JWT, if its issuance time is recent enough.
+    fn verify_jwt(&self, token: &str) -> Option<Arc<Client>> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.required_spec_claims.clear();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        self.jwt_secrets.iter().find_map(|(secret, client)| {
+            let claims = jsonwebtoken::decode::<Claims>(token, secret, &validation).ok()?.claims;
+            (claims.iat.abs_diff(now) <= JWT_MAX_IAT_DIFF).then(|| client.clone())
+        })
+    }
+}
+
+/// The clients of the keys file, reloaded when the file changes.
+pub struct Authenticator {
+    path: PathBuf,
+    keys: RwLock<(String, Arc<Clients>)>,
+}
+
+impl fmt::Debug for Authenticator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Authenticator").field("path", &self.path).finish_non_exhaustive()
+    }
+}
+
+impl Authenticator {
+    /// Loads the clients of the keys file.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the file can't be read or is invalid.
+    pub fn load(path: PathBuf) -> Result<Self, AuthError> {
+        let content = read_keys_file(&path)?;
+        let clients = parse_clients(&path, &content)?;
+        Ok(Self { path, keys: RwLock::new((content, Arc::new(clients))) })
+    }
+
+    /// Reloads the clients if the keys file changed. Returns whether they were reloaded.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the file can't be read or is invalid, in which case the previous
+    /// clients are kept.
+    pub fn reload(&self) -> Result<bool, AuthError> {
+        let content = read_keys_file(&self.path)?;
+        if self.keys.read().unwrap_or_else(PoisonError::into_inner).0 == content {
+            return Ok(false);
+        }
+        let clients = parse_clients(&self.path, &content)?;
+        *self.keys.write().unwrap_or_else(PoisonError::into_inner) = (content, Arc::new(clients));
+        Ok(true)
+    }
+
+    fn clients(&self) -> Arc<Clients> {
+        self.keys.read().unwrap_or_else(PoisonError::into_inner).1.clone()
+    }
+}
+
+fn read_keys_file(path: &Path) -> Result<String, AuthError> {
+    std::fs::read_to_string(path).map_err(|err| AuthError::Io(path.to_path_buf(), err))
+}
+
+fn parse_clients(path: &Path, content: &str) -> Result<Clients, AuthError> {
+    content.parse().map_err(|err| AuthError::InvalidKeysFile(path.to_path_buf(), err))
+}
+
+/// Reloads the keys file every `KEYS_RELOAD_INTERVAL`, until the authenticator is dropped.
+pub async fn reload_keys(authenticator: Weak<Authenticator>) {
+    let mut interval = tokio::time::interval(KEYS_RELOAD_INTERVAL);
+    // The first tick completes immediately, right after the keys were loaded.
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        let Some(authenticator) = authenticator.upgrade() else {
+            return;
+        };
+        match authenticator.reload() {
+            Ok(true) => tracing::info!("Reloaded the keys file {}", authenticator.path.display()),
+            Ok(false) => {}
+            Err(err) => tracing::warn!("Failed to reload the keys, keeping the previous ones: {err}"),
+        }
+    }
+}
+
+/// HTTP layer rejecting the requests without a valid API key or JWT with a `401 Unauthorized`
+/// status. The CORS preflight requests and the health, readiness and metrics probes are public.
+#[derive(Debug, Clone, Default)]
+pub struct AuthLayer {
+    authenticator: Option<Arc<Authenticator>>,
+}
+
+impl AuthLayer {
+    pub const fn new(authenticator: Option<Arc<Authenticator>>) -> Self {
+        Self { authenticator }
+    }
+}
+
+impl<S> Layer<S> for AuthLayer {
+    type Service = AuthService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AuthService { inner, authenticator: self.authenticator.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuthService<S> {
+    inner: S,
+    authenticator: Option<Arc<Authenticator>>,
+}
+
+impl<S> Service<Request<Body>> for AuthService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: Request<Body>) -> Self::Future {
+        let Some(authenticator) = &self.authenticator else {
+            return self.inner.call(request).boxed();
+        };
+        if is_public(&request) {
+            return self.inner.call(request).boxed();
+        }
+
+        match authenticator.clients().authenticate(&request) {
+            Ok((client, api_key_in_path)) => {
+                if api_key_in_path {
+                    // The API key is removed from the path, the RPC module is served at the root.
+                    let path_and_query =
+                        request.uri().query().map_or_else(|| "/".to_string(), |query| format!("/?{query}"));
+                    *request.uri_mut() = path_and_query.parse().expect("Failed to parse the root path");
+                }
+                request.extensions_mut().insert(client.clone());
+                AUTHENTICATED_CLIENT.scope(client, self.inner.call(request)).boxed()
+            }
+            Err(reason) => {
+                let response = Response::builder()
+                    .status(StatusCode::UNAUTHORIZED)
+                    .body(Body::from(reason))
+                    .expect("Failed to build the unauthorized response");
+                future::ok(response).boxed()
+            }
+        }
+    }
+}
+
+/// Returns whether the request can be served without credentials.
+fn is_public(request: &Request<Body>) -> bool {
+    let path = request.uri().path();
+    request.method() == Method::OPTIONS
+        || (request.method() == Method::GET && [HEALTH_PATH, READY_PATH, METRICS_PATH].contains(&path))
+}
+
+/// RPC layer rejecting the calls of the authenticated clients to the namespaces they can't call.
+/// When the authentication is enabled, the calls made outside of an authenticated HTTP request
+/// are rejected as well.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RpcAuthLayer {
+    enabled: bool,
+}
+
+impl RpcAuthLayer {
+    pub const fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl<S> Layer<S> for RpcAuthLayer {
+    type Service = RpcAuth<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcAuth { service, enabled: self.enabled }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcAuth<S> {
+    service: S,
+    enabled: bool,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcAuth<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+{
+    type Future = Either<S::Future, Ready<MethodResponse>>;
+
+    fn call(&self, request: jsonrpsee::types::Request<'a>) -> Self::Future {
+        let allowed =
+            AUTHENTICATED_CLIENT.try_with(|client| client.is_allowed(request.method_name())).unwrap_or(!self.enabled);
+        if !allowed {
+            let error = rpc_err(
+                EthRpcErrorCode::MethodNotFound,
+                format!("Method {} is not allowed for this client", request.method_name()),
+            );
+            return Either::Right(future::ready(MethodResponse::error(request.id, error)));
+        }
+        Either::Left(self.service.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+
+    const JWT_SECRET: [u8; 32] = [7; 32];
+
+    fn clients() -> Clients {
+        format!(
+            r#"
+            [[clients]]
+            name = "partner"
+            api_key = "partner-key"
+            namespaces = ["eth", "net"]
+
+            [[clients]]
+            name = "sequencer"
+            jwt_secret = "0x{}"
+            "#,
+            hex::encode(JWT_SECRET)
+        )
+        .parse()
+        .unwrap()
+    }
+
+    fn request(uri: &str, headers: &[(&str, &str)]) -> Request<Body> {
+        let mut builder = Request::builder().method(Method::POST).uri(uri);
+        for (name, value) in headers {
+            builder = builder.header(*name, *value);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    fn jwt(iat: u64) -> String {
+        jsonwebtoken::encode(&Header::default(), &json!({ "iat": iat }), &EncodingKey::from_secret(&JWT_SECRET))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_authenticate_api_key() {
+        // Given
+        let clients = clients();
+
+        // When
+        let (header_client, header_in_path) =
+            clients.authenticate(&request("/", &[(API_KEY_HEADER, "partner-key")])).unwrap();
+        let (path_client, path_in_path) = clients.authenticate(&request("/partner-key", &[])).unwrap();
+
+        // Then
+        assert_eq!(header_client.name, "partner");
+        assert!(!header_in_path);
+        assert_eq!(path_client.name, "partner");
+        assert!(path_in_path);
+        assert_eq!(clients.authenticate(&request("/", &[(API_KEY_HEADER, "unknown")])).err(), Some("Invalid API key"));
+        assert_eq!(clients.authenticate(&request("/", &[])).err(), Some("Missing API key or JWT"));
+    }
+
+    #[test]
+    fn test_authenticate_jwt() {
+        // Given
+        let clients = clients();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+        // When
+        let bearer = |iat| format!("Bearer {}", jwt(iat));
+        let valid = clients.authenticate(&request("/", &[("authorization", &bearer(now))]));
+        let stale = clients.authenticate(&request("/", &[("authorization", &bearer(now - 120))]));
+
+        // Then
+        assert_eq!(valid.unwrap().0.name, "sequencer");
+        assert_eq!(stale.err(), Some("Invalid JWT"));
+    }
+
+    #[test]
+    fn test_client_namespaces() {
+        // Given
+        let client = Client { name: "partner".to_string(), namespaces: Some(vec![KakarotRpcModule::Eth]) };
+
+        // Then
+        assert!(client.is_allowed("eth_chainId"));
+        assert!(client.is_allowed("rpc_modules"));
+        assert!(client.is_allowed("net_health"));
+        assert!(!client.is_allowed("alchemy_getTokenBalances"));
+        assert!(Client { namespaces: None, ..client }.is_allowed("alchemy_getTokenBalances"));
+    }
+
+    #[test]
+    fn test_invalid_keys_file() {
+        // Given
+        let invalid = [
+            r#"[[clients]]
+            name = "both"
+            api_key = "key"
+            jwt_secret = "0x00""#,
+            r#"[[clients]]
+            name = "short secret"
+            jwt_secret = "0x00""#,
+            r#"[[clients]]
+            name = "unknown namespace"
+            api_key = "key"
+            namespaces = ["debug"]"#,
+        ];
+
+        // Then
+        for content in invalid {
+            assert!(content.parse::<Clients>().is_err(), "{content}");
+        }
+    }
+
+    #[test]
+    fn test_reload() {
+        // Given
+        let path = std::env::temp_dir().join(format!("kakarot-rpc-keys-{}.toml", std::process::id()));
+        std::fs::write(&path, "[[clients]]\nname = \"old\"\napi_key = \"old-key\"\n").unwrap();
+        let authenticator = Authenticator::load(path.clone()).unwrap();
+        assert!(!authenticator.reload().unwrap());
+
+        // When
+        std::fs::write(&path, "[[clients]]\nname = \"new\"\napi_key = \"new-key\"\n").unwrap();
+        let reloaded = authenticator.reload().unwrap();
+        std::fs::write(&path, "invalid").unwrap();
+        let invalid = authenticator.reload();
+        std::fs::remove_file(&path).unwrap();
+
+        // Then
+        assert!(reloaded);
+        assert!(invalid.is_err());
+        // The previous keys are kept when the file is invalid
+        let clients = authenticator.clients();
+        assert!(clients.api_keys.contains_key("new-key"));
+        assert!(!clients.api_keys.contains_key("old-key"));
+    }
+}
@@ -0,0 +1,160 @@
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures::future::{self, BoxFuture, FutureExt};
+use hyper::header::CONTENT_TYPE;
+use hyper::{Body, Method, Request, Response};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::MethodResponse;
+use prometheus::TEXT_FORMAT;
+use tower::{Layer, Service};
+
+use crate::eth_provider::error::EthRpcErrorCode;
+use crate::metrics::{self, RPC_ERRORS, RPC_REQUESTS, RPC_REQUEST_DURATION};
+
+/// Path of the Prometheus metrics.
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Label of the requests to methods which aren't served, to bound the number of labels.
+const UNKNOWN_METHOD: &str = "unknown";
+
+/// HTTP layer serving the Prometheus metrics on `GET /metrics`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetricsLayer;
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = MetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MetricsService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for MetricsService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if request.method() == Method::GET && request.uri().path() == METRICS_PATH {
+            let response = Response::builder()
+                .header(CONTENT_TYPE, TEXT_FORMAT)
+                .body(Body::from(metrics::encode()))
+                .expect("Failed to build the metrics response");
+            return future::ok(response).boxed();
+        }
+        self.inner.call(request).boxed()
+    }
+}
+
+/// RPC layer recording the number of requests, errors and the latency of each method of a deployment.
+#[derive(Debug, Clone)]
+pub struct RpcMetricsLayer {
+    chain: String,
+}
+
+impl RpcMetricsLayer {
+    /// Returns a layer labelling the metrics with the name of the deployment.
+    pub const fn new(chain: String) -> Self {
+        Self { chain }
+    }
+}
+
+impl<S> Layer<S> for RpcMetricsLayer {
+    type Service = RpcMetrics<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcMetrics { service, chain: self.chain.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcMetrics<S> {
+    service: S,
+    chain: String,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcMetrics<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: jsonrpsee::types::Request<'a>) -> Self::Future {
+        let chain = self.chain.clone();
+        let method = request.method_name().to_string();
+        let start = Instant::now();
+        let response = self.service.call(request);
+
+        async move {
+            let response = response.await;
+            let code = response.as_error_code().map(EthRpcErrorCode::from);
+            // The names of the unknown methods are chosen by the callers, they share a label
+            let method = if code == Some(EthRpcErrorCode::MethodNotFound) { UNKNOWN_METHOD } else { &method };
+
+            RPC_REQUESTS.with_label_values(&[&chain, method]).inc();
+            RPC_REQUEST_DURATION.with_label_values(&[&chain, method]).observe(start.elapsed().as_secs_f64());
+            if let Some(code) = code {
+                RPC_ERRORS.with_label_values(&[&chain, method, &format!("{code:?}")]).inc();
+            }
+            response
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_service() {
+        // Given
+        RPC_REQUESTS.with_label_values(&["devnet", "eth_chainId"]).inc();
+        let service = hyper::service::service_fn(|_: Request<Body>| async {
+            Ok::<_, std::convert::Infallible>(Response::new(Body::from("rpc")))
+        });
+        let mut service = MetricsLayer.layer(service);
+
+        // When
+        let request = Request::builder().method(Method::GET).uri(METRICS_PATH).body(Body::empty()).unwrap();
+        let response = service.call(request).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        // Then
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("kakarot_rpc_requests_total{chain=\"devnet\",method=\"eth_chainId\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_service_forwards_other_requests() {
+        // Given
+        let service = hyper::service::service_fn(|_: Request<Body>| async {
+            Ok::<_, std::convert::Infallible>(Response::new(Body::from("rpc")))
+        });
+        let mut service = MetricsLayer.layer(service);
+
+        // When
+        let request = Request::builder().method(Method::POST).uri("/").body(Body::empty()).unwrap();
+        let response = service.call(request).await.unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        // Then
+        assert_eq!(body.as_ref(), b"rpc");
+    }
+}
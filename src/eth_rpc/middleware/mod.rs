@@ -0,0 +1,6 @@
+pub mod auth;
+pub mod metrics;
+pub mod rate_limit;
+pub mod readiness;
+pub mod timeout;
+pub mod trace_context;
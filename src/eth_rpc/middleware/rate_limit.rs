@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use futures::future::{self, BoxFuture, Either, FutureExt, Ready};
+use hyper::{Body, Request, Response};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::MethodResponse;
+use tower::{Layer, Service};
+
+use super::auth::Client;
+use crate::eth_provider::error::{rpc_err, EthRpcErrorCode};
+use crate::eth_rpc::config::RateLimitConfig;
+
+/// Header of the addresses of the client and of the proxies the request went through.
+const FORWARDED_FOR_HEADER: &str = "x-forwarded-for";
+
+/// Client of the calls made outside of an HTTP request, which share a single bucket.
+const UNKNOWN_CLIENT: &str = "unknown";
+
+/// Number of buckets from which the full ones, i.e. the buckets of the idle clients, are dropped.
+const MAX_BUCKETS: usize = 10_000;
+
+tokio::task_local! {
+    /// The client of the HTTP request being processed.
+    static CLIENT: String;
+}
+
+/// Token buckets of the clients. Each request takes the weight of its method from the bucket
+/// of its client, which is refilled at a constant rate up to the burst size.
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    /// Returns the tokens of the bucket at `now`.
+    fn refilled(&self, config: &RateLimitConfig, now: Instant) -> f64 {
+        let refill = now.saturating_duration_since(self.updated_at).as_secs_f64() * f64::from(config.per_second);
+        (self.tokens + refill).min(f64::from(config.burst))
+    }
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self { config, buckets: Mutex::new(HashMap::new()) }
+    }
+
+    /// Takes the weight of the method from the bucket of the client. Returns `false` if the
+    /// bucket doesn't hold enough tokens, in which case the request must be rejected.
+    pub fn try_acquire(&self, client: &str, method: &str) -> bool {
+        self.try_acquire_at(client, method, Instant::now())
+    }
+
+    fn try_acquire_at(&self, client: &str, method: &str, now: Instant) -> bool {
+        let weight = f64::from(self.config.weight(method));
+        let burst = f64::from(self.config.burst);
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if buckets.len() >= MAX_BUCKETS {
+            buckets.retain(|_, bucket| bucket.refilled(&self.config, now) < burst);
+        }
+
+        let bucket = buckets.entry(client.to_string()).or_insert(TokenBucket { tokens: burst, updated_at: now });
+        bucket.tokens = bucket.refilled(&self.config, now);
+        bucket.updated_at = now;
+
+        if bucket.tokens < weight {
+            return false;
+        }
+        bucket.tokens -= weight;
+        true
+    }
+}
+
+/// HTTP layer identifying the client of each request for the rate limiting: by its name if it
+/// was authenticated, else by its IP address. The API keys of the unauthenticated requests
+/// aren't verified, so they can't identify their clients.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClientLayer {
+    trust_forwarded_for: bool,
+}
+
+impl ClientLayer {
+    pub const fn new(trust_forwarded_for: bool) -> Self {
+        Self { trust_forwarded_for }
+    }
+}
+
+impl<S> Layer<S> for ClientLayer {
+    type Service = ClientService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientService { inner, trust_forwarded_for: self.trust_forwarded_for }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientService<S> {
+    inner: S,
+    trust_forwarded_for: bool,
+}
+
+impl<S> Service<Request<Body>> for ClientService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        match client(&request, self.trust_forwarded_for) {
+            Some(client) => CLIENT.scope(client, self.inner.call(request)).boxed(),
+            None => self.inner.call(request).boxed(),
+        }
+    }
+}
+
+/// Returns the key of the client of the request, prefixed by the way it was identified.
+fn client(request: &Request<Body>, trust_forwarded_for: bool) -> Option<String> {
+    let header = |name: &str| request.headers().get(name).and_then(|value| value.to_str().ok());
+
+    if let Some(client) = request.extensions().get::<Arc<Client>>() {
+        return Some(format!("client:{}", client.name));
+    }
+    let forwarded_for = header(FORWARDED_FOR_HEADER)
+        .filter(|_| trust_forwarded_for)
+        .and_then(|addresses| addresses.split(',').next())
+        .map(str::trim)
+        .filter(|address| !address.is_empty());
+    if let Some(address) = forwarded_for {
+        return Some(format!("ip:{address}"));
+    }
+    request.extensions().get::<SocketAddr>().map(|remote_addr| format!("ip:{}", remote_addr.ip()))
+}
+
+/// RPC layer rejecting the requests of the clients which exceeded their rate limit with a
+/// `RequestLimitExceeded` error. Requests without a client, which the server doesn't serve as
+/// it rejects the WebSocket upgrades, share a single bucket rather than escaping the limits.
+#[derive(Debug, Clone, Default)]
+pub struct RpcRateLimitLayer {
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl RpcRateLimitLayer {
+    pub const fn new(rate_limiter: Option<Arc<RateLimiter>>) -> Self {
+        Self { rate_limiter }
+    }
+}
+
+impl<S> Layer<S> for RpcRateLimitLayer {
+    type Service = RpcRateLimit<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcRateLimit { service, rate_limiter: self.rate_limiter.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcRateLimit<S> {
+    service: S,
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcRateLimit<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+{
+    type Future = Either<S::Future, Ready<MethodResponse>>;
+
+    fn call(&self, request: jsonrpsee::types::Request<'a>) -> Self::Future {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            let limited = CLIENT
+                .try_with(|client| !rate_limiter.try_acquire(client, request.method_name()))
+                .unwrap_or_else(|_| !rate_limiter.try_acquire(UNKNOWN_CLIENT, request.method_name()));
+            if limited {
+                let error = rpc_err(
+                    EthRpcErrorCode::RequestLimitExceeded,
+                    format!("Rate limit exceeded for {}", request.method_name()),
+                );
+                return Either::Right(future::ready(MethodResponse::error(request.id, error)));
+            }
+        }
+        Either::Left(self.service.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::eth_rpc::middleware::auth::API_KEY_HEADER;
+
+    fn rate_limiter(per_second: u32, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig { burst, ..RateLimitConfig::new(per_second) })
+    }
+
+    #[test]
+    fn test_rate_limiter_burst() {
+        // Given
+        let rate_limiter = rate_limiter(1, 3);
+        let now = Instant::now();
+
+        // When
+        let accepted =
+            (0..4).map(|_| rate_limiter.try_acquire_at("ip:127.0.0.1", "eth_chainId", now)).collect::<Vec<_>>();
+
+        // Then
+        assert_eq!(accepted, vec![true, true, true, false]);
+        // The other clients have their own bucket
+        assert!(rate_limiter.try_acquire_at("ip:127.0.0.2", "eth_chainId", now));
+    }
+
+    #[test]
+    fn test_rate_limiter_refill() {
+        // Given
+        let rate_limiter = rate_limiter(2, 2);
+        let now = Instant::now();
+        assert!(rate_limiter.try_acquire_at("client:test", "eth_chainId", now));
+        assert!(rate_limiter.try_acquire_at("client:test", "eth_chainId", now));
+        assert!(!rate_limiter.try_acquire_at("client:test", "eth_chainId", now));
+
+        // When
+        let later = now + Duration::from_millis(500);
+
+        // Then
+        assert!(rate_limiter.try_acquire_at("client:test", "eth_chainId", later));
+        assert!(!rate_limiter.try_acquire_at("client:test", "eth_chainId", later));
+    }
+
+    #[test]
+    fn test_rate_limiter_method_weights() {
+        // Given
+        let rate_limiter = rate_limiter(1, 12);
+        let now = Instant::now();
+
+        // When
+        let get_logs = rate_limiter.try_acquire_at("ip:127.0.0.1", "eth_getLogs", now);
+        let call = rate_limiter.try_acquire_at("ip:127.0.0.1", "eth_call", now);
+        let chain_id = rate_limiter.try_acquire_at("ip:127.0.0.1", "eth_chainId", now);
+
+        // Then
+        // eth_getLogs weighs 10, eth_call 5 and eth_chainId 1
+        assert!(get_logs);
+        assert!(!call);
+        assert!(chain_id);
+    }
+
+    #[test]
+    fn test_client() {
+        // Given
+        let remote_addr: SocketAddr = "10.0.0.1:4242".parse().unwrap();
+        let request = |headers: &[(&str, &str)]| {
+            let mut builder = Request::builder().uri("/");
+            for (name, value) in headers {
+                builder = builder.header(*name, *value);
+            }
+            let mut request = builder.body(Body::empty()).unwrap();
+            request.extensions_mut().insert(remote_addr);
+            request
+        };
+
+        // When
+        let by_ip = client(&request(&[]), false);
+        let unverified_key = client(&request(&[(API_KEY_HEADER, "secret")]), false);
+        let untrusted_proxy = client(&request(&[(FORWARDED_FOR_HEADER, "1.2.3.4, 10.0.0.2")]), false);
+        let trusted_proxy = client(&request(&[(FORWARDED_FOR_HEADER, "1.2.3.4, 10.0.0.2")]), true);
+        let mut authenticated_request = request(&[(API_KEY_HEADER, "secret")]);
+        authenticated_request
+            .extensions_mut()
+            .insert(Arc::new(Client { name: "partner".to_string(), namespaces: None }));
+        let authenticated = client(&authenticated_request, false);
+
+        // Then
+        assert_eq!(by_ip.as_deref(), Some("ip:10.0.0.1"));
+        // Unverified API keys are ignored, a client can't get a fresh bucket with a fresh key
+        assert_eq!(unverified_key.as_deref(), Some("ip:10.0.0.1"));
+        assert_eq!(untrusted_proxy.as_deref(), Some("ip:10.0.0.1"));
+        assert_eq!(trusted_proxy.as_deref(), Some("ip:1.2.3.4"));
+        assert_eq!(authenticated.as_deref(), Some("client:partner"));
+    }
+}
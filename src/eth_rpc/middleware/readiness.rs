@@ -0,0 +1,160 @@
+use std::task::{Context, Poll};
+
+use futures::future::{BoxFuture, FutureExt};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use tower::{Layer, Service};
+
+use crate::eth_rpc::rpc::HEALTH_METHOD;
+use crate::models::health::HealthReport;
+
+/// Path of the readiness probe.
+pub const READY_PATH: &str = "/ready";
+
+/// JSON-RPC request sent to the RPC module to get the health report.
+fn health_request() -> String {
+    format!(r#"{{"jsonrpc":"2.0","method":"{HEALTH_METHOD}","params":[],"id":0}}"#)
+}
+
+/// HTTP layer answering `GET /ready` with the `net_health` report, with a `200 OK` status
+/// if the RPC is healthy and a `503 Service Unavailable` status otherwise, so load balancers
+/// can route around the stale replicas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadinessLayer;
+
+impl<S> Layer<S> for ReadinessLayer {
+    type Service = ReadinessService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ReadinessService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadinessService<S> {
+    inner: S,
+}
+
+impl<S> Service<Request<Body>> for ReadinessService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>>,
+    S::Error: Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if request.method() != Method::GET || request.uri().path() != READY_PATH {
+            return self.inner.call(request).boxed();
+        }
+
+        let health_request = Request::builder()
+            .method(Method::POST)
+            .uri("/")
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(Body::from(health_request()))
+            .expect("Failed to build the health request");
+        let response = self.inner.call(health_request);
+
+        async move {
+            let body = hyper::body::to_bytes(response.await?.into_body()).await;
+            Ok(readiness_response(body.ok().and_then(|body| serde_json::from_slice::<HealthResponse>(&body).ok())))
+        }
+        .boxed()
+    }
+}
+
+/// Response of the RPC module to the health request.
+#[derive(Deserialize)]
+struct HealthResponse {
+    result: HealthReport,
+}
+
+/// Returns the readiness response, with the report in the body.
+fn readiness_response(health: Option<HealthResponse>) -> Response<Body> {
+    let (status, body) = match health {
+        Some(HealthResponse { result: report }) => {
+            let status = if report.healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+            (status, serde_json::to_string(&report).expect("Failed to serialize the health report"))
+        }
+        None => (StatusCode::SERVICE_UNAVAILABLE, r#"{"healthy":false}"#.to_string()),
+    };
+
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+        .body(Body::from(body))
+        .expect("Failed to build the readiness response")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the readiness service of a RPC module answering the health request with `result`.
+    fn readiness_service(
+        result: &'static str,
+    ) -> ReadinessService<impl Service<Request<Body>, Response = Response<Body>, Error = std::convert::Infallible>>
+    {
+        let rpc = hyper::service::service_fn(move |request: Request<Body>| async move {
+            assert_eq!(request.method(), Method::POST);
+            let body = format!(r#"{{"jsonrpc":"2.0","result":{result},"id":0}}"#);
+            Ok::<_, std::convert::Infallible>(Response::new(Body::from(body)))
+        });
+        ReadinessLayer.layer(rpc)
+    }
+
+    fn ready_request() -> Request<Body> {
+        Request::builder().method(Method::GET).uri(READY_PATH).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_readiness_healthy() {
+        // Given
+        let mut service = readiness_service(
+            r#"{"healthy":true,"mongo":{"healthy":true,"block_number":10},"starknet":{"healthy":true,"block_number":12},"indexer":{"healthy":true,"lag":2,"max_lag":10}}"#,
+        );
+
+        // When
+        let response = service.call(ready_request()).await.unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let report: HealthReport = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report.indexer.lag, Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_readiness_stale_indexer() {
+        // Given
+        let mut service = readiness_service(
+            r#"{"healthy":false,"mongo":{"healthy":true,"block_number":10},"starknet":{"healthy":true,"block_number":50},"indexer":{"healthy":false,"lag":40,"max_lag":10}}"#,
+        );
+
+        // When
+        let response = service.call(ready_request()).await.unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_rpc_error() {
+        // Given
+        let mut service = readiness_service("null");
+
+        // When
+        let response = service.call(ready_request()).await.unwrap();
+
+        // Then
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
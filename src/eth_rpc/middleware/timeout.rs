@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use futures::future::{BoxFuture, FutureExt};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::MethodResponse;
+use tower::Layer;
+
+use crate::eth_provider::error::{rpc_err, EthRpcErrorCode};
+use crate::eth_rpc::config::TimeoutConfig;
+
+/// RPC layer cancelling the method calls which exceed their timeout, and answering them with a
+/// `ResourceUnavailable` error, the code used by geth for timeouts. Dropping the call drops the
+/// pending Mongo queries and Starknet requests.
+#[derive(Debug, Clone, Default)]
+pub struct RpcTimeoutLayer {
+    timeouts: Arc<TimeoutConfig>,
+}
+
+impl RpcTimeoutLayer {
+    pub fn new(timeouts: TimeoutConfig) -> Self {
+        Self { timeouts: Arc::new(timeouts) }
+    }
+}
+
+impl<S> Layer<S> for RpcTimeoutLayer {
+    type Service = RpcTimeout<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RpcTimeout { service, timeouts: self.timeouts.clone() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RpcTimeout<S> {
+    service: S,
+    timeouts: Arc<TimeoutConfig>,
+}
+
+impl<'a, S> RpcServiceT<'a> for RpcTimeout<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+    S::Future: 'a,
+{
+    type Future = BoxFuture<'a, MethodResponse>;
+
+    fn call(&self, request: jsonrpsee::types::Request<'a>) -> Self::Future {
+        let timeout = self.timeouts.timeout(request.method_name());
+        let id = request.id.clone();
+        let response = self.service.call(request);
+
+        async move {
+            match tokio::time::timeout(timeout, response).await {
+                Ok(response) => response,
+                Err(_) => {
+                    let error = rpc_err(
+                        EthRpcErrorCode::ResourceUnavailable,
+                        format!("Request timed out after {}ms", timeout.as_millis()),
+                    );
+                    MethodResponse::error(id, error)
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use jsonrpsee::types::{Id, Request};
+
+    use super::*;
+
+    /// RPC service answering every call after `delay` with an internal error.
+    struct Delayed {
+        delay: Duration,
+    }
+
+    impl<'a> RpcServiceT<'a> for Delayed {
+        type Future = BoxFuture<'a, MethodResponse>;
+
+        fn call(&self, request: Request<'a>) -> Self::Future {
+            let delay = self.delay;
+            async move {
+                tokio::time::sleep(delay).await;
+                MethodResponse::error(request.id, rpc_err(EthRpcErrorCode::InternalError, "done"))
+            }
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rpc_timeout() {
+        // Given
+        let timeouts = TimeoutConfig {
+            default: Duration::from_millis(50),
+            methods: [("eth_getLogs".to_string(), Duration::from_secs(5))].into_iter().collect(),
+        };
+        let service = RpcTimeoutLayer::new(timeouts).layer(Delayed { delay: Duration::from_millis(200) });
+
+        // When
+        let timed_out = service.call(Request::new("eth_call".into(), None, Id::Number(1))).await;
+        let completed = service.call(Request::new("eth_getLogs".into(), None, Id::Number(2))).await;
+
+        // Then
+        assert_eq!(timed_out.as_error_code(), Some(EthRpcErrorCode::ResourceUnavailable as i32));
+        assert_eq!(completed.as_error_code(), Some(EthRpcErrorCode::InternalError as i32));
+    }
+}
@@ -1,20 +1,48 @@
 // //! Kakarot RPC module for Ethereum.
 // //! It is an adapter layer to interact with Kakarot ZK-EVM.
+use std::collections::HashMap;
+use std::io::ErrorKind;
 use std::net::{AddrParseError, SocketAddr};
+use std::sync::Arc;
 
-use config::RPCConfig;
+use config::{RPCConfig, TimeoutConfig};
 pub mod api;
 pub mod config;
+pub mod middleware;
 pub mod rpc;
 pub mod servers;
 
 use eyre::Result;
+use futures::future::{self, FutureExt};
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
 use jsonrpsee::server::middleware::http::{InvalidPath, ProxyGetRequestLayer};
-use jsonrpsee::server::{ServerBuilder, ServerHandle};
-use jsonrpsee::RpcModule;
+use jsonrpsee::server::middleware::rpc::RpcServiceBuilder;
+use jsonrpsee::server::{stop_channel, BatchRequestConfig, ServerBuilder, ServerHandle};
+use jsonrpsee::{Methods, RpcModule};
 use thiserror::Error;
+use tokio::sync::Semaphore;
+use tower::layer::util::{Identity, Stack};
+use tower::{Service, ServiceBuilder};
 use tower_http::cors::{Any, CorsLayer};
 
+use middleware::auth::{reload_keys, AuthError, AuthLayer, Authenticator, RpcAuthLayer};
+use middleware::metrics::{MetricsLayer, RpcMetricsLayer, METRICS_PATH};
+use middleware::rate_limit::{ClientLayer, RateLimiter, RpcRateLimitLayer};
+use middleware::readiness::ReadinessLayer;
+use middleware::timeout::RpcTimeoutLayer;
+use middleware::trace_context::TraceContextLayer;
+use rpc::HEALTH_METHOD;
+
+use crate::metrics::DEFAULT_CHAIN;
+
+/// Path of the health check, proxied to `net_health`.
+pub const HEALTH_PATH: &str = "/health";
+
+/// Prefix of the paths under which the named Kakarot deployments are served.
+const CHAIN_PATH_PREFIX: &str = "/chain/";
+
 #[derive(Error, Debug)]
 pub enum RpcError {
     #[error(transparent)]
@@ -23,31 +51,289 @@ pub enum RpcError {
     ParseError(#[from] AddrParseError),
     #[error(transparent)]
     JsonRpcError(#[from] InvalidPath),
+    #[error(transparent)]
+    HyperError(#[from] hyper::Error),
+    #[error(transparent)]
+    AuthError(#[from] AuthError),
+}
+
+/// Layers of the HTTP middleware of the server, from the innermost.
+type HttpMiddleware = Stack<
+    MetricsLayer,
+    Stack<
+        CorsLayer,
+        Stack<
+            ReadinessLayer,
+            Stack<ProxyGetRequestLayer, Stack<ClientLayer, Stack<AuthLayer, Stack<TraceContextLayer, Identity>>>>,
+        >,
+    >,
+>;
+
+/// Layers of the RPC middleware of the server, from the innermost.
+type RpcMiddleware =
+    Stack<RpcTimeoutLayer, Stack<RpcRateLimitLayer, Stack<RpcAuthLayer, Stack<RpcMetricsLayer, Identity>>>>;
+
+/// Returns the HTTP middleware of the server, continuing the trace of the caller, authenticating
+/// and identifying the client, proxying `GET /health` to `net_health`, answering `GET /ready` with the health
+/// report and serving the Prometheus metrics on `GET /metrics`.
+fn http_middleware(
+    authenticator: Option<Arc<Authenticator>>,
+    trust_forwarded_for: bool,
+) -> Result<ServiceBuilder<HttpMiddleware>, RpcError> {
+    let cors = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any);
+    Ok(ServiceBuilder::new()
+        .layer(TraceContextLayer)
+        .layer(AuthLayer::new(authenticator))
+        .layer(ClientLayer::new(trust_forwarded_for))
+        .layer(ProxyGetRequestLayer::new(HEALTH_PATH, HEALTH_METHOD)?)
+        .layer(ReadinessLayer)
+        .layer(cors)
+        .layer(MetricsLayer))
+}
+
+/// Returns the RPC middleware of the deployment, recording the metrics of each method, restricting
+/// the authenticated clients to their namespaces, rate limiting the clients and cancelling the
+/// calls exceeding their timeout.
+fn rpc_middleware(
+    chain: String,
+    authenticated: bool,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    timeouts: TimeoutConfig,
+) -> RpcServiceBuilder<RpcMiddleware> {
+    RpcServiceBuilder::new()
+        .layer(RpcMetricsLayer::new(chain))
+        .layer(RpcAuthLayer::new(authenticated))
+        .layer(RpcRateLimitLayer::new(rate_limiter))
+        .layer(RpcTimeoutLayer::new(timeouts))
+}
+
+/// The RPC modules served by the server.
+enum Routes {
+    /// A single module, served on every path.
+    Single(Methods),
+    /// The modules of the named Kakarot deployments, served under `/chain/<name>`.
+    Chains(HashMap<String, Methods>),
+}
+
+impl Routes {
+    /// Returns the modules by name, the single module being named [`DEFAULT_CHAIN`].
+    fn modules(&self) -> Vec<(String, Methods)> {
+        match self {
+            Self::Single(methods) => vec![(DEFAULT_CHAIN.to_string(), methods.clone())],
+            Self::Chains(chains) => chains.iter().map(|(name, methods)| (name.clone(), methods.clone())).collect(),
+        }
+    }
+
+    /// Returns the name of the module serving the request, along with the request to forward to it.
+    /// The metrics are shared by the deployments, so any of them serves `GET /metrics` at the root.
+    fn route(&self, request: Request<Body>) -> Option<(String, Request<Body>)> {
+        match self {
+            Self::Single(_) => Some((DEFAULT_CHAIN.to_string(), request)),
+            Self::Chains(chains) if request.method() == Method::GET && request.uri().path() == METRICS_PATH => {
+                chains.keys().min().map(|name| (name.clone(), request))
+            }
+            Self::Chains(_) => split_chain_path(request),
+        }
+    }
 }
 
 /// # Errors
 ///
-/// Will return `Err` if an error occurs when running the `ServerBuilder` start fails.
+/// Will return `Err` if the socket address can't be parsed or bound, or if the keys file
+/// can't be loaded.
 pub async fn run_server(
     kakarot_rpc_module: RpcModule<()>,
     rpc_config: RPCConfig,
 ) -> Result<(SocketAddr, ServerHandle), RpcError> {
-    let RPCConfig { socket_addr } = rpc_config;
+    serve(Routes::Single(kakarot_rpc_module.into()), rpc_config)
+}
 
-    let cors = CorsLayer::new().allow_methods(Any).allow_origin(Any).allow_headers(Any);
+/// Runs a server serving the RPC module of each named Kakarot deployment under
+/// `/chain/<name>`, e.g. `/chain/devnet` and `/chain/devnet/health`. The deployments
+/// share the listener, the connection limit, the rate limits and the HTTP middleware, and
+/// the metrics are served at the root on `GET /metrics`.
+///
+/// # Errors
+///
+/// Will return `Err` if the socket address can't be parsed or bound, or if the keys file
+/// can't be loaded.
+pub async fn run_multi_chain_server(
+    kakarot_rpc_modules: HashMap<String, RpcModule<()>>,
+    rpc_config: RPCConfig,
+) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    let chains = kakarot_rpc_modules.into_iter().map(|(name, module)| (name, module.into())).collect();
+    serve(Routes::Chains(chains), rpc_config)
+}
+
+/// Binds the socket address and serves the routes on a spawned task until the returned
+/// handle is stopped. The remote address of each connection is added to the extensions of
+/// its requests, to identify the clients. The keys file, if any, is reloaded until the server
+/// is stopped.
+fn serve(routes: Routes, rpc_config: RPCConfig) -> Result<(SocketAddr, ServerHandle), RpcError> {
+    let RPCConfig {
+        socket_addr,
+        max_connections,
+        rate_limit,
+        auth_keys_file,
+        max_batch_size,
+        max_request_size,
+        max_response_size,
+        timeouts,
+    } = rpc_config;
+
+    let authenticator = auth_keys_file.map(Authenticator::load).transpose()?.map(Arc::new);
+    if let Some(authenticator) = &authenticator {
+        tokio::spawn(reload_keys(Arc::downgrade(authenticator)));
+    }
+    let authenticated = authenticator.is_some();
+    let trust_forwarded_for = rate_limit.as_ref().is_some_and(|rate_limit| rate_limit.trust_forwarded_for);
+    let rate_limiter = rate_limit.map(|rate_limit| Arc::new(RateLimiter::new(rate_limit)));
+    let batch_request_config = match max_batch_size {
+        0 => BatchRequestConfig::Disabled,
+        max_batch_size => BatchRequestConfig::Limit(max_batch_size),
+    };
+    let http_middleware = http_middleware(authenticator, trust_forwarded_for)?;
+    // Each module gets its own RPC middleware, to label its metrics with the name of the deployment.
+    let modules = routes
+        .modules()
+        .into_iter()
+        .map(|(name, methods)| {
+            let rpc_middleware = rpc_middleware(name.clone(), authenticated, rate_limiter.clone(), timeouts.clone());
+            // The calls of a WebSocket connection run on a task spawned by the server, out of the scope
+            // of the client identified by the HTTP middleware, so the WebSocket upgrades are rejected.
+            let service_builder = ServerBuilder::default()
+                .http_only()
+                .max_request_body_size(max_request_size)
+                .max_response_body_size(max_response_size)
+                .set_batch_request_config(batch_request_config)
+                .set_rpc_middleware(rpc_middleware)
+                .set_http_middleware(http_middleware.clone())
+                .to_service_builder();
+            (name, methods, service_builder)
+        })
+        .collect::<Vec<_>>();
+    let routes = Arc::new(routes);
+    let connections = Arc::new(Semaphore::new(max_connections as usize));
+    let (stop_handle, server_handle) = stop_channel();
+
+    let make_service = {
+        let stop_handle = stop_handle.clone();
+        make_service_fn(move |connection: &AddrStream| {
+            let remote_addr = connection.remote_addr();
+            // The permit is held by the service of the connection, until the connection is closed.
+            let Ok(permit) = connections.clone().try_acquire_owned() else {
+                return future::ready(Err(std::io::Error::new(ErrorKind::Other, "Too many connections")));
+            };
+
+            // Each connection gets a service per module, the request path selects the one to call.
+            let routes = routes.clone();
+            let mut services = modules
+                .iter()
+                .map(|(name, methods, service_builder)| {
+                    (name.clone(), service_builder.build(methods.clone(), stop_handle.clone()))
+                })
+                .collect::<HashMap<_, _>>();
+            future::ready(Ok(service_fn(move |mut request: Request<Body>| {
+                let _permit = &permit;
+                request.extensions_mut().insert(remote_addr);
+                match routes
+                    .route(request)
+                    .and_then(|(name, request)| services.get_mut(&name).map(|service| service.call(request)))
+                {
+                    Some(response) => response.boxed(),
+                    None => {
+                        let response = Response::builder()
+                            .status(StatusCode::NOT_FOUND)
+                            .body(Body::from("Unknown chain, expected a /chain/<name> path"))
+                            .expect("Failed to build the not found response");
+                        future::ok(response).boxed()
+                    }
+                }
+            })))
+        })
+    };
+
+    let server = hyper::Server::try_bind(&socket_addr.parse::<SocketAddr>()?)?.serve(make_service);
+    let addr = server.local_addr();
+    let server = server.with_graceful_shutdown(async move { stop_handle.shutdown().await });
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            tracing::error!("RPC server error: {err}");
+        }
+    });
+
+    Ok((addr, server_handle))
+}
+
+/// Splits the `/chain/<name>` prefix from the path of the request. Returns the name of
+/// the deployment and the request to forward to its RPC module, or `None` if the path
+/// doesn't start with a deployment.
+fn split_chain_path(mut request: Request<Body>) -> Option<(String, Request<Body>)> {
+    let path = request.uri().path().strip_prefix(CHAIN_PATH_PREFIX)?;
+    let (name, path) = match path.split_once('/') {
+        Some((name, path)) => (name.to_string(), format!("/{path}")),
+        None => (path.to_string(), "/".to_string()),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    let path_and_query = match request.uri().query() {
+        Some(query) => format!("{path}?{query}"),
+        None => path,
+    };
+    *request.uri_mut() = path_and_query.parse().ok()?;
+    Some((name, request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_split_chain_path() {
+        // When
+        let (name, request) = split_chain_path(request("/chain/devnet")).unwrap();
+
+        // Then
+        assert_eq!(name, "devnet");
+        assert_eq!(request.uri(), "/");
+    }
+
+    #[test]
+    fn test_split_chain_path_with_sub_path() {
+        // When
+        let (name, request) = split_chain_path(request("/chain/staging/health?verbose=true")).unwrap();
 
-    let http_middleware =
-        tower::ServiceBuilder::new().layer(ProxyGetRequestLayer::new("/health", "net_health")?).layer(cors);
+        // Then
+        assert_eq!(name, "staging");
+        assert_eq!(request.uri(), "/health?verbose=true");
+    }
 
-    let server = ServerBuilder::default()
-        .max_connections(std::env::var("RPC_MAX_CONNECTIONS").unwrap_or_else(|_| "100".to_string()).parse().unwrap())
-        .set_http_middleware(http_middleware)
-        .build(socket_addr.parse::<SocketAddr>()?)
-        .await?;
+    #[test]
+    fn test_route_metrics_of_chains() {
+        // Given
+        let chains = ["staging", "devnet"].into_iter().map(|name| (name.to_string(), Methods::new())).collect();
+        let routes = Routes::Chains(chains);
 
-    let addr = server.local_addr()?;
+        // When
+        let metrics = Request::builder().method(Method::GET).uri(METRICS_PATH).body(Body::empty()).unwrap();
+        let (name, forwarded) = routes.route(metrics).unwrap();
 
-    let handle = server.start(kakarot_rpc_module);
+        // Then
+        assert_eq!(name, "devnet");
+        assert_eq!(forwarded.uri(), METRICS_PATH);
+        assert!(routes.route(request("/health")).is_none());
+    }
 
-    Ok((addr, handle))
+    #[test]
+    fn test_split_chain_path_without_chain() {
+        assert!(split_chain_path(request("/")).is_none());
+        assert!(split_chain_path(request("/chain/")).is_none());
+        assert!(split_chain_path(request("/health")).is_none());
+    }
 }
@@ -1,20 +1,27 @@
-use std::collections::HashMap;
-use std::marker::PhantomData;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use jsonrpsee::server::RegisterMethodError;
 use jsonrpsee::{Methods, RpcModule};
 
+use crate::accounts::AccountSigner;
 use crate::eth_provider::provider::EthereumProvider;
 use crate::eth_rpc::api::alchemy_api::AlchemyApiServer;
 use crate::eth_rpc::api::eth_api::EthApiServer;
 use crate::eth_rpc::api::net_api::NetApiServer;
+use crate::eth_rpc::api::rpc_api::RpcApiServer;
 use crate::eth_rpc::api::web3_api::Web3ApiServer;
 use crate::eth_rpc::servers::alchemy_rpc::AlchemyRpc;
 use crate::eth_rpc::servers::eth_rpc::KakarotEthRpc;
 use crate::eth_rpc::servers::net_rpc::NetRpc;
+use crate::eth_rpc::servers::rpc_rpc::RpcRpc;
 use crate::eth_rpc::servers::web3_rpc::Web3Rpc;
 
+/// Method called by the `GET /health` and `GET /ready` probes, served whatever the selection.
+pub const HEALTH_METHOD: &str = "net_health";
+
 /// Represents RPC modules that are supported by reth
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum KakarotRpcModule {
@@ -24,12 +31,72 @@ pub enum KakarotRpcModule {
     Net,
 }
 
+impl KakarotRpcModule {
+    /// All the RPC modules, enabled by default.
+    pub const ALL: [Self; 4] = [Self::Eth, Self::Alchemy, Self::Web3, Self::Net];
+
+    /// Returns the namespace of the module, i.e. the prefix of its methods.
+    pub const fn namespace(&self) -> &'static str {
+        match self {
+            Self::Eth => "eth",
+            Self::Alchemy => "alchemy",
+            Self::Web3 => "web3",
+            Self::Net => "net",
+        }
+    }
+}
+
+impl fmt::Display for KakarotRpcModule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.namespace())
+    }
+}
+
+impl FromStr for KakarotRpcModule {
+    type Err = eyre::Error;
+
+    fn from_str(namespace: &str) -> Result<Self, Self::Err> {
+        Self::ALL.into_iter().find(|module| module.namespace() == namespace.to_lowercase()).ok_or_else(|| {
+            let namespaces = Self::ALL.map(|module| module.namespace()).join(", ");
+            eyre::eyre!("unknown RPC module {namespace}, expected one of {namespaces}")
+        })
+    }
+}
+
+/// The RPC namespaces and methods served by the RPC module. The `rpc_modules` method is
+/// always served, unless denied, and the [`HEALTH_METHOD`] is always served, for the health
+/// probes of the server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcModuleSelection {
+    /// The enabled namespaces.
+    pub modules: Vec<KakarotRpcModule>,
+    /// If not empty, only these methods of the enabled namespaces are served.
+    pub allowed_methods: Vec<String>,
+    /// Methods which are not served.
+    pub denied_methods: Vec<String>,
+}
+
+impl Default for RpcModuleSelection {
+    fn default() -> Self {
+        Self { modules: KakarotRpcModule::ALL.to_vec(), allowed_methods: Vec::new(), denied_methods: Vec::new() }
+    }
+}
+
+impl RpcModuleSelection {
+    /// Returns whether the method is allowed and not denied.
+    fn is_served(&self, method: &str) -> bool {
+        let allowed = self.allowed_methods.is_empty() || self.allowed_methods.iter().any(|allowed| allowed == method);
+        allowed && !self.denied_methods.iter().any(|denied| denied == method)
+    }
+}
+
 pub struct KakarotRpcModuleBuilder<P>
 where
     P: EthereumProvider + Send + Sync,
 {
     modules: HashMap<KakarotRpcModule, Methods>,
-    _phantom: PhantomData<P>,
+    selection: RpcModuleSelection,
+    eth_provider: Arc<P>,
 }
 
 impl<P> KakarotRpcModuleBuilder<P>
@@ -41,7 +108,7 @@ where
         let eth_rpc_module = KakarotEthRpc::new(eth_provider.clone()).into_rpc();
         let alchemy_rpc_module = AlchemyRpc::new(eth_provider.clone()).into_rpc();
         let web3_rpc_module = Web3Rpc::default().into_rpc();
-        let net_rpc_module = NetRpc::new(eth_provider).into_rpc();
+        let net_rpc_module = NetRpc::new(eth_provider.clone()).into_rpc();
 
         let mut modules: HashMap<KakarotRpcModule, Methods> = HashMap::new();
 
@@ -50,14 +117,87 @@ where
         modules.insert(KakarotRpcModule::Web3, web3_rpc_module.into());
         modules.insert(KakarotRpcModule::Net, net_rpc_module.into());
 
-        Self { modules, _phantom: PhantomData }
+        Self { modules, selection: RpcModuleSelection::default(), eth_provider }
+    }
+
+    /// Manages the accounts of the signer in the eth module.
+    #[must_use]
+    pub fn with_signer(mut self, signer: AccountSigner) -> Self {
+        let eth_rpc_module = KakarotEthRpc::new(self.eth_provider.clone()).with_signer(Arc::new(signer)).into_rpc();
+        self.modules.insert(KakarotRpcModule::Eth, eth_rpc_module.into());
+        self
+    }
+
+    /// Reports the RPC as unhealthy in `net_health` when the indexer lags more than
+    /// `max_indexer_lag` blocks behind Starknet.
+    #[must_use]
+    pub fn with_max_indexer_lag(mut self, max_indexer_lag: u64) -> Self {
+        let net_rpc_module = NetRpc::new(self.eth_provider.clone()).with_max_indexer_lag(max_indexer_lag).into_rpc();
+        self.modules.insert(KakarotRpcModule::Net, net_rpc_module.into());
+        self
     }
 
+    /// Serves the selected namespaces and methods only.
+    #[must_use]
+    pub fn with_selection(mut self, selection: RpcModuleSelection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Returns the RPC module serving the selected methods.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if an allowed or denied method doesn't exist in any namespace.
     pub fn rpc_module(&self) -> Result<RpcModule<()>, RegisterMethodError> {
+        let rpc_modules_method = RpcRpc::new([]).into_rpc();
+        let known_methods = self
+            .modules
+            .values()
+            .flat_map(Methods::method_names)
+            .chain(rpc_modules_method.method_names())
+            .collect::<HashSet<_>>();
+        if let Some(unknown) = self
+            .selection
+            .allowed_methods
+            .iter()
+            .chain(&self.selection.denied_methods)
+            .find(|method| !known_methods.contains(method.as_str()))
+        {
+            return Err(RegisterMethodError::MethodNotFound(unknown.clone()));
+        }
+
         let mut rpc_module = RpcModule::new(());
+        for module in &self.selection.modules {
+            if let Some(methods) = self.modules.get(module) {
+                rpc_module.merge(methods.clone())?;
+            }
+        }
+
+        // Report the namespaces with at least one served method.
+        let mut served_methods = rpc_module
+            .method_names()
+            .chain(rpc_modules_method.method_names())
+            .filter(|method| self.selection.is_served(method))
+            .collect::<HashSet<_>>();
+        let namespaces = served_methods
+            .iter()
+            .filter_map(|method| method.split_once('_').map(|(namespace, _)| namespace))
+            .collect::<BTreeSet<_>>();
+        rpc_module.merge(RpcRpc::new(namespaces).into_rpc())?;
+
+        // The health probes must keep working when the net namespace isn't served.
+        if !rpc_module.method_names().any(|method| method == HEALTH_METHOD) {
+            if let Some(methods) = self.modules.get(&KakarotRpcModule::Net) {
+                rpc_module.merge(methods.clone())?;
+            }
+        }
+        served_methods.insert(HEALTH_METHOD);
 
-        for methods in self.modules.values().cloned() {
-            rpc_module.merge(methods)?;
+        let removed_methods =
+            rpc_module.method_names().filter(|method| !served_methods.contains(method)).collect::<Vec<_>>();
+        for method in removed_methods {
+            rpc_module.remove_method(method);
         }
 
         Ok(rpc_module)
@@ -1,6 +1,9 @@
-use crate::eth_provider::constant::MAX_PRIORITY_FEE_PER_GAS;
+use std::sync::Arc;
+
+use crate::accounts::eip712::TypedData;
+use crate::accounts::{fill_transaction_request, AccountSigner, Keystore};
 use crate::eth_provider::error::EthProviderError;
-use crate::eth_provider::provider::EthereumProvider;
+use crate::eth_provider::provider::{EthProviderResult, EthereumProvider};
 use jsonrpsee::core::{async_trait, RpcResult as Result};
 use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256, B64, U256, U64};
 use reth_rpc_types::{
@@ -17,6 +20,7 @@ where
     P: EthereumProvider,
 {
     eth_provider: P,
+    signer: Option<Arc<AccountSigner>>,
 }
 
 impl<P> KakarotEthRpc<P>
@@ -24,7 +28,45 @@ where
     P: EthereumProvider,
 {
     pub const fn new(eth_provider: P) -> Self {
-        Self { eth_provider }
+        Self { eth_provider, signer: None }
+    }
+
+    /// Sets the signer of the accounts managed by the RPC, enabling `eth_accounts`,
+    /// `eth_sendTransaction` and `eth_signTransaction`. Local accounts also enable
+    /// `eth_sign` and `eth_signTypedData`.
+    #[must_use]
+    pub fn with_signer(mut self, signer: Arc<AccountSigner>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    fn signer(&self, method: &str) -> EthProviderResult<&AccountSigner> {
+        self.signer.as_deref().ok_or_else(|| EthProviderError::MethodNotSupported(method.to_string()))
+    }
+
+    /// Returns the keystore of the local accounts. Messages are only signed by local accounts.
+    fn keystore(&self, method: &str) -> EthProviderResult<&Keystore> {
+        match self.signer(method)? {
+            AccountSigner::Keystore(keystore) => Ok(keystore),
+            AccountSigner::Remote(_) => Err(EthProviderError::MethodNotSupported(method.to_string())),
+        }
+    }
+
+    /// Fills and signs the transaction request with the managed account of the sender.
+    async fn sign_transaction_request(&self, method: &str, request: TransactionRequest) -> EthProviderResult<Bytes> {
+        let signer = self.signer(method)?;
+        if let Some(from) = request.from {
+            if !signer.contains(from) {
+                return Err(EthProviderError::UnknownAccount(from));
+            }
+        }
+
+        let request = fill_transaction_request(&self.eth_provider, request).await?;
+        let transaction = signer.sign_transaction(request).await?;
+
+        let mut bytes = Vec::new();
+        transaction.encode_enveloped(&mut bytes);
+        Ok(bytes.into())
     }
 }
 
@@ -43,13 +85,14 @@ where
         Ok(self.eth_provider.syncing().await?)
     }
 
+    #[tracing::instrument(skip_all, ret, err)]
     async fn coinbase(&self) -> Result<Address> {
-        Err(EthProviderError::MethodNotSupported("eth_coinbase".to_string()).into())
+        Ok(self.eth_provider.coinbase().await?)
     }
 
     #[tracing::instrument(skip_all, ret, err)]
     async fn accounts(&self) -> Result<Vec<Address>> {
-        Ok(Vec::new())
+        Ok(self.signer.as_ref().map(|signer| signer.addresses()).unwrap_or_default())
     }
 
     #[tracing::instrument(skip_all, ret, err)]
@@ -189,7 +232,7 @@ where
 
     #[tracing::instrument(skip_all, ret, err)]
     async fn max_priority_fee_per_gas(&self) -> Result<U256> {
-        Ok(U256::from(*MAX_PRIORITY_FEE_PER_GAS))
+        Ok(self.eth_provider.max_priority_fee_per_gas().await?)
     }
 
     async fn blob_base_fee(&self) -> Result<U256> {
@@ -219,8 +262,10 @@ where
         Err(EthProviderError::MethodNotSupported("eth_submitWork".to_string()).into())
     }
 
-    async fn send_transaction(&self, _request: TransactionRequest) -> Result<B256> {
-        Err(EthProviderError::MethodNotSupported("eth_sendTransaction".to_string()).into())
+    #[tracing::instrument(skip_all, ret, err)]
+    async fn send_transaction(&self, request: TransactionRequest) -> Result<B256> {
+        let bytes = self.sign_transaction_request("eth_sendTransaction", request).await?;
+        Ok(self.eth_provider.send_raw_transaction(bytes).await?)
     }
 
     #[tracing::instrument(skip_all, ret, err, fields(bytes = %bytes))]
@@ -228,25 +273,26 @@ where
         Ok(self.eth_provider.send_raw_transaction(bytes).await?)
     }
 
-    async fn sign(&self, _address: Address, _message: Bytes) -> Result<Bytes> {
-        Err(EthProviderError::MethodNotSupported("eth_sign".to_string()).into())
+    async fn sign(&self, address: Address, message: Bytes) -> Result<Bytes> {
+        Ok(self.keystore("eth_sign")?.sign_message(address, &message)?)
     }
 
-    async fn sign_transaction(&self, _transaction: TransactionRequest) -> Result<Bytes> {
-        Err(EthProviderError::MethodNotSupported("eth_signTransaction".to_string()).into())
+    async fn sign_transaction(&self, transaction: TransactionRequest) -> Result<Bytes> {
+        Ok(self.sign_transaction_request("eth_signTransaction", transaction).await?)
     }
 
-    async fn sign_typed_data(&self, _address: Address, _data: Value) -> Result<Bytes> {
-        Err(EthProviderError::MethodNotSupported("eth_signTypedData".to_string()).into())
+    async fn sign_typed_data(&self, address: Address, data: Value) -> Result<Bytes> {
+        let keystore = self.keystore("eth_signTypedData")?;
+        Ok(keystore.sign_typed_data(address, &TypedData::from_value(data)?)?)
     }
 
     async fn get_proof(
         &self,
-        _address: Address,
-        _keys: Vec<B256>,
-        _block_id: Option<BlockId>,
+        address: Address,
+        keys: Vec<B256>,
+        block_id: Option<BlockId>,
     ) -> Result<EIP1186AccountProofResponse> {
-        Err(EthProviderError::MethodNotSupported("eth_getProof".to_string()).into())
+        Ok(self.eth_provider.get_proof(address, keys, block_id).await?)
     }
 
     async fn new_filter(&self, _filter: Filter) -> Result<U64> {
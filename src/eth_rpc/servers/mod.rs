@@ -1,4 +1,5 @@
 pub mod alchemy_rpc;
 pub mod eth_rpc;
 pub mod net_rpc;
+pub mod rpc_rpc;
 pub mod web3_rpc;
@@ -4,15 +4,24 @@ use reth_primitives::U64;
 use reth_rpc_types::PeerCount;
 
 use crate::eth_rpc::api::net_api::NetApiServer;
+use crate::models::health::{HealthReport, DEFAULT_MAX_INDEXER_LAG};
 
 /// The RPC module for the implementing Net api
 pub struct NetRpc<P: EthereumProvider> {
     eth_provider: P,
+    max_indexer_lag: u64,
 }
 
 impl<P: EthereumProvider> NetRpc<P> {
     pub const fn new(eth_provider: P) -> Self {
-        Self { eth_provider }
+        Self { eth_provider, max_indexer_lag: DEFAULT_MAX_INDEXER_LAG }
+    }
+
+    /// Sets the maximum number of blocks the indexer can lag behind Starknet while healthy.
+    #[must_use]
+    pub const fn with_max_indexer_lag(mut self, max_indexer_lag: u64) -> Self {
+        self.max_indexer_lag = max_indexer_lag;
+        self
     }
 }
 
@@ -32,10 +41,8 @@ impl<P: EthereumProvider + Send + Sync + 'static> NetApiServer for NetRpc<P> {
         Ok(false)
     }
 
-    async fn health(&self) -> Result<bool> {
-        // Calls starknet block_number method to check if it resolves
-        let _ = self.eth_provider.block_number().await?;
-
-        Ok(true)
+    async fn health(&self) -> Result<HealthReport> {
+        let health = self.eth_provider.health().await;
+        Ok(HealthReport::new(health, self.max_indexer_lag))
     }
 }
@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use jsonrpsee::core::{async_trait, RpcResult as Result};
+
+use crate::eth_rpc::api::rpc_api::RpcApiServer;
+
+/// Version reported for each enabled namespace, as done by geth and reth.
+const MODULE_VERSION: &str = "1.0";
+
+/// The RPC module for the implementing Rpc Api { i.e rpc endpoints prefixed with rpc_ }
+pub struct RpcRpc {
+    modules: BTreeMap<String, String>,
+}
+
+impl RpcRpc {
+    pub fn new<'a>(namespaces: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            modules: namespaces
+                .into_iter()
+                .map(|namespace| (namespace.to_string(), MODULE_VERSION.to_string()))
+                .collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl RpcApiServer for RpcRpc {
+    fn modules(&self) -> Result<BTreeMap<String, String>> {
+        Ok(self.modules.clone())
+    }
+}
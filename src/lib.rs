@@ -1,6 +1,10 @@
+pub mod accounts;
+pub mod cli;
 pub mod config;
 pub mod eth_provider;
 pub mod eth_rpc;
+pub mod metrics;
 pub mod models;
+pub mod telemetry;
 #[cfg(feature = "testing")]
 pub mod test_utils;
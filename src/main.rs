@@ -1,21 +1,29 @@
-use std::env::var;
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use clap::Parser;
 use dotenv::dotenv;
-use eyre::Result;
-use kakarot_rpc::config::{JsonRpcClientBuilder, KakarotRpcConfig, Network, SequencerGatewayProviderBuilder};
+use eyre::{eyre, Result};
+use jsonrpsee::RpcModule;
+use kakarot_rpc::accounts::AccountSigner;
+use kakarot_rpc::cli::{ChainConfig, Chains, Cli, Config};
+use kakarot_rpc::config::{JsonRpcClientBuilder, Network, SequencerGatewayProviderBuilder};
+use kakarot_rpc::eth_provider::cache::{CacheConfig, CachingProvider};
 use kakarot_rpc::eth_provider::database::Database;
+use kakarot_rpc::eth_provider::gas_oracle::GasPriceOracleConfig;
 use kakarot_rpc::eth_provider::provider::EthDataProvider;
-use kakarot_rpc::eth_rpc::config::RPCConfig;
-use kakarot_rpc::eth_rpc::rpc::KakarotRpcModuleBuilder;
-use kakarot_rpc::eth_rpc::run_server;
+use kakarot_rpc::eth_provider::starknet::failover::{FailoverConfig, FailoverTransport};
+use kakarot_rpc::eth_provider::starknet::proof::StarknetProofClient;
+use kakarot_rpc::eth_provider::starknet::transport::InstrumentedTransport;
+use kakarot_rpc::eth_rpc::rpc::{KakarotRpcModuleBuilder, RpcModuleSelection};
+use kakarot_rpc::eth_rpc::{run_multi_chain_server, run_server};
+use kakarot_rpc::metrics::{report_indexer_lag, DEFAULT_CHAIN};
+use kakarot_rpc::telemetry;
 use mongodb::options::{DatabaseOptions, ReadConcern, WriteConcern};
-use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{JsonRpcClient, SequencerGatewayProvider};
-use tracing_subscriber::util::SubscriberInitExt;
 
 enum StarknetProvider {
-    JsonRpcClient(JsonRpcClient<HttpTransport>),
+    JsonRpcClient(JsonRpcClient<InstrumentedTransport<FailoverTransport>>, StarknetProofClient),
     SequencerGatewayProvider(SequencerGatewayProvider),
 }
 
@@ -23,71 +31,184 @@ enum StarknetProvider {
 async fn main() -> Result<()> {
     dotenv().ok();
     // Environment variables are safe to use after this
-    let filter = tracing_subscriber::EnvFilter::try_from_default_env()?;
-    tracing_subscriber::FmtSubscriber::builder().with_env_filter(filter).finish().try_init()?;
+    let cli = Cli::parse();
+    let settings = cli.resolve_settings()?;
+    if cli.print_config {
+        print!("{}", settings.redacted().to_toml()?);
+        return Ok(());
+    }
+    let Config {
+        rpc: rpc_config,
+        api,
+        chains,
+        cache,
+        max_indexer_lag,
+        otlp_endpoint,
+        gas_price_oracle: gas_price_oracle_config,
+        accounts,
+    } = settings.validate()?;
+
+    telemetry::init(otlp_endpoint.as_deref())?;
+
+    let signer = accounts.signer()?;
+
+    let (server_addr, server_handle, paths) = match chains {
+        Chains::Single(chain) => {
+            // Get the deployer nonce and set the value in the DEPLOY_WALLET_NONCE
+            #[cfg(feature = "hive")]
+            {
+                use kakarot_rpc::eth_provider::constant::{deploy_wallet, DEPLOY_WALLET, DEPLOY_WALLET_NONCE};
+                use starknet::accounts::ConnectedAccount;
+                use starknet::providers::jsonrpc::HttpTransport;
+                use starknet::providers::Provider;
+                let provider_url = chain.kakarot_rpc.network.provider_url()?;
+                let provider = JsonRpcClient::new(HttpTransport::new(provider_url.clone()));
+
+                let chain_id = provider.chain_id().await?;
+                let wallet = deploy_wallet(provider_url, chain_id)?;
+
+                let deployer_nonce = wallet.get_nonce().await?;
+                let mut nonce = DEPLOY_WALLET_NONCE.lock().await;
+                *nonce = deployer_nonce;
+                DEPLOY_WALLET.set(wallet).map_err(|_| eyre!("Failed to set the deploy wallet"))?;
+            }
+
+            let kakarot_rpc_module =
+                kakarot_rpc_module(DEFAULT_CHAIN, chain, &api, cache, max_indexer_lag, gas_price_oracle_config, signer)
+                    .await?;
+            let (server_addr, server_handle) = run_server(kakarot_rpc_module, rpc_config).await?;
+            (server_addr, server_handle, vec![String::new()])
+        }
+        Chains::Named(chains) => {
+            if cfg!(feature = "hive") {
+                return Err(eyre!("The hive feature only supports a single chain"));
+            }
+
+            let mut kakarot_rpc_modules = HashMap::with_capacity(chains.len());
+            for (name, chain) in chains {
+                let kakarot_rpc_module = kakarot_rpc_module(
+                    &name,
+                    chain,
+                    &api,
+                    cache,
+                    max_indexer_lag,
+                    gas_price_oracle_config,
+                    signer.clone(),
+                )
+                .await?;
+                kakarot_rpc_modules.insert(name, kakarot_rpc_module);
+            }
+            let paths = kakarot_rpc_modules.keys().map(|name| format!("/chain/{name}")).collect();
+            let (server_addr, server_handle) = run_multi_chain_server(kakarot_rpc_modules, rpc_config).await?;
+            (server_addr, server_handle, paths)
+        }
+    };
+
+    for path in paths {
+        let url = format!("http://{server_addr}{path}");
 
-    let starknet_config = KakarotRpcConfig::from_env()?;
+        println!("RPC Server running on {url}...");
+    }
+
+    server_handle.stopped().await;
+    telemetry::shutdown();
+
+    Ok(())
+}
 
-    let rpc_config = RPCConfig::from_env()?;
+/// Returns the RPC module of the Kakarot deployment, reading from its own Starknet provider
+/// and Mongo database through the caches. Also reports the indexer lag of the deployment in
+/// the metrics.
+async fn kakarot_rpc_module(
+    name: &str,
+    chain: ChainConfig,
+    api: &RpcModuleSelection,
+    cache: CacheConfig,
+    max_indexer_lag: u64,
+    gas_price_oracle_config: GasPriceOracleConfig,
+    signer: Option<AccountSigner>,
+) -> Result<RpcModule<()>> {
+    let ChainConfig {
+        kakarot_rpc: starknet_config,
+        starknet_fallback_urls,
+        mongo_connection_string,
+        mongo_database_name,
+    } = chain;
+    let kakarot_deployment = starknet_config.kakarot_deployment();
 
     let starknet_provider = match &starknet_config.network {
-        Network::Madara | Network::Katana | Network::Sharingan => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::with_http(&starknet_config).unwrap().build())
-        }
-        Network::JsonRpcProvider(url) => {
-            StarknetProvider::JsonRpcClient(JsonRpcClientBuilder::new(HttpTransport::new(url.clone())).build())
+        Network::Madara | Network::Katana | Network::Sharingan | Network::JsonRpcProvider(_) => {
+            let transport = FailoverTransport::new(
+                starknet_config.network.provider_url()?,
+                starknet_fallback_urls,
+                FailoverConfig::default(),
+            );
+            tokio::spawn(transport.health_check());
+            let proof_client = StarknetProofClient::new(transport.clone());
+            StarknetProvider::JsonRpcClient(
+                JsonRpcClientBuilder::new(InstrumentedTransport::new(transport)).build(),
+                proof_client,
+            )
         }
         _ => StarknetProvider::SequencerGatewayProvider(
             SequencerGatewayProviderBuilder::new(&starknet_config.network).build(),
         ),
     };
 
-    let db_client =
-        mongodb::Client::with_uri_str(var("MONGO_CONNECTION_STRING").expect("Missing MONGO_CONNECTION_STRING .env"))
-            .await?;
+    let db_client = mongodb::Client::with_uri_str(mongo_connection_string).await?;
     let db = Database::new(db_client.database_with_options(
-        &var("MONGO_DATABASE_NAME").expect("Missing MONGO_DATABASE_NAME from .env"),
+        &mongo_database_name,
         DatabaseOptions::builder().read_concern(ReadConcern::MAJORITY).write_concern(WriteConcern::MAJORITY).build(),
     ));
 
-    // Get the deployer nonce and set the value in the DEPLOY_WALLET_NONCE
-    #[cfg(feature = "hive")]
-    {
-        use kakarot_rpc::eth_provider::constant::{CHAIN_ID, DEPLOY_WALLET, DEPLOY_WALLET_NONCE};
-        use starknet::accounts::ConnectedAccount;
-        use starknet::providers::Provider;
-        let provider = JsonRpcClient::new(HttpTransport::new(
-            starknet_config.network.provider_url().expect("Incorrect provider URL"),
-        ));
-
-        let chain_id = provider.chain_id().await?;
-        CHAIN_ID.set(chain_id).expect("Failed to set chain id");
-
-        let deployer_nonce = DEPLOY_WALLET.get_nonce().await?;
-        let mut nonce = DEPLOY_WALLET_NONCE.lock().await;
-        *nonce = deployer_nonce;
-    }
-
     let kakarot_rpc_module = match starknet_provider {
-        StarknetProvider::JsonRpcClient(starknet_provider) => {
+        StarknetProvider::JsonRpcClient(starknet_provider, proof_client) => {
             let starknet_provider = Arc::new(starknet_provider);
-            let eth_provider = EthDataProvider::new(db, starknet_provider);
-            KakarotRpcModuleBuilder::new(eth_provider).rpc_module()
+            let eth_provider = Arc::new(
+                EthDataProvider::new(db, starknet_provider, kakarot_deployment)
+                    .with_gas_price_oracle_config(gas_price_oracle_config)
+                    .with_values_ttl(cache.values_ttl)
+                    .with_proof_client(proof_client),
+            );
+            load_static_values(name, &eth_provider).await;
+            tokio::spawn(report_indexer_lag(name.to_string(), eth_provider.clone()));
+            let mut builder = KakarotRpcModuleBuilder::new(CachingProvider::new(eth_provider, cache))
+                .with_selection(api.clone())
+                .with_max_indexer_lag(max_indexer_lag);
+            if let Some(signer) = signer {
+                builder = builder.with_signer(signer);
+            }
+            builder.rpc_module()
         }
         StarknetProvider::SequencerGatewayProvider(starknet_provider) => {
             let starknet_provider = Arc::new(starknet_provider);
-            let eth_provider = EthDataProvider::new(db, starknet_provider);
-            KakarotRpcModuleBuilder::new(eth_provider).rpc_module()
+            let eth_provider = Arc::new(
+                EthDataProvider::new(db, starknet_provider, kakarot_deployment)
+                    .with_gas_price_oracle_config(gas_price_oracle_config)
+                    .with_values_ttl(cache.values_ttl),
+            );
+            load_static_values(name, &eth_provider).await;
+            tokio::spawn(report_indexer_lag(name.to_string(), eth_provider.clone()));
+            let mut builder = KakarotRpcModuleBuilder::new(CachingProvider::new(eth_provider, cache))
+                .with_selection(api.clone())
+                .with_max_indexer_lag(max_indexer_lag);
+            if let Some(signer) = signer {
+                builder = builder.with_signer(signer);
+            }
+            builder.rpc_module()
         }
     }?;
 
-    let (server_addr, server_handle) = run_server(kakarot_rpc_module, rpc_config).await?;
-
-    let url = format!("http://{server_addr}");
-
-    println!("RPC Server running on {url}...");
-
-    server_handle.stopped().await;
+    Ok(kakarot_rpc_module)
+}
 
-    Ok(())
+/// Fetches the static values of the deployment at startup. The RPC starts anyway if they can't
+/// be fetched, in which case they are fetched by the first requests using them.
+async fn load_static_values<SP>(name: &str, eth_provider: &EthDataProvider<SP>)
+where
+    SP: starknet::providers::Provider + Send + Sync,
+{
+    if let Err(err) = eth_provider.load_static_values().await {
+        tracing::warn!("Failed to load the static values of chain {name}: {err}");
+    }
 }
@@ -0,0 +1,104 @@
+//! Prometheus metrics of the Kakarot RPC, served on `GET /metrics`.
+use std::sync::Arc;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use prometheus::{
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge_vec, Encoder,
+    HistogramVec, IntCounter, IntCounterVec, IntGaugeVec, TextEncoder,
+};
+
+use crate::eth_provider::provider::EthDataProvider;
+
+/// Name of the deployment in the metrics when a single one is served.
+pub const DEFAULT_CHAIN: &str = "default";
+
+/// Interval between two updates of the indexer head lag.
+pub const INDEXER_LAG_INTERVAL: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    /// Number of RPC requests, by chain and method.
+    pub static ref RPC_REQUESTS: IntCounterVec =
+        register_int_counter_vec!("kakarot_rpc_requests_total", "Number of RPC requests", &["chain", "method"])
+            .expect("Failed to register the RPC requests counter");
+    /// Number of failed RPC requests, by chain, method and `EthRpcErrorCode`.
+    pub static ref RPC_ERRORS: IntCounterVec = register_int_counter_vec!(
+        "kakarot_rpc_errors_total",
+        "Number of failed RPC requests",
+        &["chain", "method", "code"]
+    )
+    .expect("Failed to register the RPC errors counter");
+    /// Latency of the RPC requests, by chain and method.
+    pub static ref RPC_REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        "kakarot_rpc_request_duration_seconds",
+        "Latency of the RPC requests",
+        &["chain", "method"]
+    )
+    .expect("Failed to register the RPC latency histogram");
+    /// Latency of the Mongo queries, by operation and collection.
+    pub static ref MONGO_QUERY_DURATION: HistogramVec = register_histogram_vec!(
+        "kakarot_mongo_query_duration_seconds",
+        "Latency of the Mongo queries",
+        &["operation", "collection"]
+    )
+    .expect("Failed to register the Mongo latency histogram");
+    /// Latency of the Starknet provider requests, by Starknet JSON-RPC method.
+    pub static ref STARKNET_REQUEST_DURATION: HistogramVec = register_histogram_vec!(
+        "kakarot_starknet_request_duration_seconds",
+        "Latency of the Starknet provider requests",
+        &["method"]
+    )
+    .expect("Failed to register the Starknet latency histogram");
+    /// Number of Starknet calls merged into an identical call in flight.
+    pub static ref STARKNET_COALESCED_CALLS: IntCounter = register_int_counter!(
+        "kakarot_starknet_coalesced_calls_total",
+        "Number of Starknet calls merged into an identical call in flight"
+    )
+    .expect("Failed to register the coalesced calls counter");
+    /// Health of the Starknet upstreams, 1 if healthy and 0 if ejected, by host.
+    pub static ref STARKNET_UPSTREAM_HEALTHY: IntGaugeVec = register_int_gauge_vec!(
+        "kakarot_starknet_upstream_healthy",
+        "Health of the Starknet upstreams",
+        &["upstream"]
+    )
+    .expect("Failed to register the Starknet upstreams gauge");
+    /// Number of hits of the provider caches, by cache.
+    pub static ref CACHE_HITS: IntCounterVec =
+        register_int_counter_vec!("kakarot_cache_hits_total", "Number of hits of the provider caches", &["cache"])
+            .expect("Failed to register the cache hits counter");
+    /// Number of misses of the provider caches, by cache.
+    pub static ref CACHE_MISSES: IntCounterVec =
+        register_int_counter_vec!("kakarot_cache_misses_total", "Number of misses of the provider caches", &["cache"])
+            .expect("Failed to register the cache misses counter");
+    /// Number of Starknet blocks not indexed yet, by chain.
+    pub static ref INDEXER_HEAD_LAG: IntGaugeVec = register_int_gauge_vec!(
+        "kakarot_indexer_head_lag_blocks",
+        "Number of blocks between the indexed head and the Starknet head",
+        &["chain"]
+    )
+    .expect("Failed to register the indexer lag gauge");
+}
+
+/// Returns the registered metrics in the Prometheus text format.
+pub fn encode() -> String {
+    let mut buffer = Vec::new();
+    if let Err(err) = TextEncoder::new().encode(&prometheus::gather(), &mut buffer) {
+        tracing::error!("Failed to encode the metrics: {err}");
+    }
+    String::from_utf8(buffer).unwrap_or_default()
+}
+
+/// Updates the indexer head lag of the chain every [`INDEXER_LAG_INTERVAL`], forever.
+pub async fn report_indexer_lag<SP>(chain: String, eth_provider: Arc<EthDataProvider<SP>>)
+where
+    SP: starknet::providers::Provider + Send + Sync,
+{
+    let mut interval = tokio::time::interval(INDEXER_LAG_INTERVAL);
+    loop {
+        interval.tick().await;
+        match eth_provider.indexer_lag().await {
+            Ok(lag) => INDEXER_HEAD_LAG.with_label_values(&[&chain]).set(i64::try_from(lag).unwrap_or(i64::MAX)),
+            Err(err) => tracing::warn!("Failed to compute the indexer lag of chain {chain}: {err}"),
+        }
+    }
+}
@@ -0,0 +1,319 @@
+use reth_primitives::{
+    AccessList, Header, Log, Receipt, ReceiptWithBloom, Signature, Transaction, TransactionKind, TransactionSigned,
+    TxEip1559, TxEip2930, TxLegacy, TxType, B256, B64,
+};
+use reth_rpc_types::{Header as RpcHeader, Transaction as RpcTransaction, TransactionReceipt as RpcTransactionReceipt};
+
+use crate::models::errors::ConversionError;
+
+/// Converts an indexed header into its consensus representation.
+/// The hash of the returned header is not checked, as Kakarot block hashes are the hashes of
+/// the underlying Starknet blocks. For the same reason, its parent hash is the hash of the parent
+/// Starknet block, see [`link_header`].
+pub fn into_consensus_header(header: RpcHeader) -> Result<Header, ConversionError> {
+    let number = header.number.ok_or_else(|| ConversionError::Other("missing block number".to_string()))?;
+
+    Ok(Header {
+        parent_hash: header.parent_hash,
+        ommers_hash: header.uncles_hash,
+        beneficiary: header.miner,
+        state_root: header.state_root,
+        transactions_root: header.transactions_root,
+        receipts_root: header.receipts_root,
+        withdrawals_root: header.withdrawals_root,
+        logs_bloom: header.logs_bloom,
+        difficulty: header.difficulty,
+        number: u64::try_from(number)?,
+        gas_limit: u64::try_from(header.gas_limit)?,
+        gas_used: u64::try_from(header.gas_used)?,
+        timestamp: u64::try_from(header.timestamp)?,
+        mix_hash: Option::<B256>::from(header.mix_hash).unwrap_or_default(),
+        nonce: Option::<B64>::from(header.nonce).map(|nonce| u64::from_be_bytes(nonce.0)).unwrap_or_default(),
+        base_fee_per_gas: header.base_fee_per_gas.map(u64::try_from).transpose()?,
+        blob_gas_used: header.blob_gas_used.map(|gas| gas.to::<u64>()),
+        excess_blob_gas: header.excess_blob_gas.map(|gas| gas.to::<u64>()),
+        parent_beacon_block_root: header.parent_beacon_block_root,
+        extra_data: header.extra_data,
+    })
+}
+
+/// Sets the parent hash of the consensus header to the hash of its re-encoded parent, if any, and
+/// returns the hash of the header. Linking each header to the previous one makes the chain of
+/// consensus headers valid under keccak hashing.
+pub fn link_header(header: &mut Header, parent_hash: Option<B256>) -> B256 {
+    if let Some(parent_hash) = parent_hash {
+        header.parent_hash = parent_hash;
+    }
+    header.hash_slow()
+}
+
+/// Converts an indexed transaction into a signed transaction. The hash of the
+/// rebuilt transaction is checked against the indexed one.
+pub fn into_signed_transaction(transaction: RpcTransaction) -> Result<TransactionSigned, ConversionError> {
+    let rpc_signature =
+        transaction.signature.ok_or_else(|| ConversionError::Other("missing transaction signature".to_string()))?;
+    let v = u64::try_from(rpc_signature.v)?;
+    let signature = Signature {
+        r: rpc_signature.r,
+        s: rpc_signature.s,
+        odd_y_parity: match v {
+            0 | 1 => v == 1,
+            27 | 28 => v == 28,
+            v if v >= 35 => (v - 35) % 2 == 1,
+            _ => return Err(ConversionError::ValueOutOfRange(format!("signature v {v}"))),
+        },
+    };
+
+    let missing = |field: &str| ConversionError::Other(format!("missing transaction {field}"));
+    let chain_id = transaction.chain_id.map(|chain_id| chain_id.to::<u64>());
+    let nonce = transaction.nonce.to::<u64>();
+    let gas_limit = u64::try_from(transaction.gas)?;
+    let to = transaction.to.map_or(TransactionKind::Create, TransactionKind::Call);
+    let value = transaction.value.into();
+    let input = transaction.input;
+    // The RPC and the consensus access lists share the same serialization.
+    let access_list = || -> Result<AccessList, ConversionError> {
+        let access_list = serde_json::to_value(&transaction.access_list)
+            .and_then(serde_json::from_value::<Option<AccessList>>)
+            .map_err(|err| ConversionError::Other(format!("invalid access list: {err}")))?;
+        Ok(access_list.unwrap_or_default())
+    };
+
+    let tx_type = transaction.transaction_type.map(|tx_type| tx_type.to::<u64>()).unwrap_or_default();
+    let tx = match tx_type {
+        0 => Transaction::Legacy(TxLegacy {
+            // Pre EIP-155 transactions don't commit to the chain id.
+            chain_id: if v >= 35 { chain_id.or(Some((v - 35) / 2)) } else { None },
+            nonce,
+            gas_price: u128::try_from(transaction.gas_price.ok_or_else(|| missing("gas price"))?)?,
+            gas_limit,
+            to,
+            value,
+            input,
+        }),
+        1 => Transaction::Eip2930(TxEip2930 {
+            chain_id: chain_id.ok_or_else(|| missing("chain id"))?,
+            nonce,
+            gas_price: u128::try_from(transaction.gas_price.ok_or_else(|| missing("gas price"))?)?,
+            gas_limit,
+            to,
+            value,
+            access_list: access_list()?,
+            input,
+        }),
+        2 => Transaction::Eip1559(TxEip1559 {
+            chain_id: chain_id.ok_or_else(|| missing("chain id"))?,
+            nonce,
+            gas_limit,
+            max_fee_per_gas: u128::try_from(transaction.max_fee_per_gas.ok_or_else(|| missing("max fee per gas"))?)?,
+            max_priority_fee_per_gas: u128::try_from(
+                transaction.max_priority_fee_per_gas.ok_or_else(|| missing("max priority fee per gas"))?,
+            )?,
+            to,
+            value,
+            access_list: access_list()?,
+            input,
+        }),
+        tx_type => return Err(ConversionError::Other(format!("unsupported transaction type {tx_type}"))),
+    };
+
+    let signed = TransactionSigned::from_transaction_and_signature(tx, signature);
+    if signed.hash != transaction.hash {
+        return Err(ConversionError::Other(format!(
+            "rebuilt transaction hash {} doesn't match the indexed hash {}",
+            signed.hash, transaction.hash
+        )));
+    }
+    Ok(signed)
+}
+
+/// Converts an indexed receipt into its consensus representation.
+pub fn into_consensus_receipt(receipt: RpcTransactionReceipt) -> Result<ReceiptWithBloom, ConversionError> {
+    let tx_type = match receipt.transaction_type.to::<u8>() {
+        0 => TxType::Legacy,
+        1 => TxType::EIP2930,
+        2 => TxType::EIP1559,
+        tx_type => return Err(ConversionError::Other(format!("unsupported transaction type {tx_type}"))),
+    };
+    let success = receipt.status_code.is_some_and(|status| status.to::<u64>() == 1);
+
+    Ok(ReceiptWithBloom {
+        receipt: Receipt {
+            tx_type,
+            success,
+            cumulative_gas_used: u64::try_from(receipt.cumulative_gas_used)?,
+            logs: receipt
+                .logs
+                .into_iter()
+                .map(|log| Log { address: log.address, topics: log.topics, data: log.data })
+                .collect(),
+        },
+        bloom: receipt.logs_bloom,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use reth_primitives::{Address, Bytes, U256};
+    use serde_json::json;
+
+    use super::*;
+    use crate::accounts::Keystore;
+
+    // Anvil first account
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    fn sign(transaction: Transaction) -> TransactionSigned {
+        let keystore = Keystore::from_private_keys([B256::from_str(PRIVATE_KEY).unwrap()]).unwrap();
+        keystore.sign_transaction(keystore.addresses()[0], transaction).unwrap()
+    }
+
+    /// Returns the transaction as returned by the RPC and stored in the database.
+    fn to_rpc_transaction(transaction: &TransactionSigned) -> RpcTransaction {
+        let parity = u64::from(transaction.signature.odd_y_parity);
+        let (tx_type, v) = match transaction.transaction {
+            Transaction::Legacy(_) => (0, transaction.signature.v(transaction.chain_id())),
+            Transaction::Eip2930(_) => (1, parity),
+            _ => (2, parity),
+        };
+        serde_json::from_value(json!({
+            "hash": transaction.hash,
+            "nonce": U256::from(transaction.nonce()),
+            "from": transaction.recover_signer().unwrap(),
+            "to": transaction.to(),
+            "value": U256::from(transaction.value()),
+            "gasPrice": (tx_type < 2).then(|| U256::from(transaction.max_fee_per_gas())),
+            "gas": U256::from(transaction.gas_limit()),
+            "maxFeePerGas": (tx_type == 2).then(|| U256::from(transaction.max_fee_per_gas())),
+            "maxPriorityFeePerGas": transaction.max_priority_fee_per_gas().map(U256::from),
+            "input": transaction.input(),
+            "r": transaction.signature.r,
+            "s": transaction.signature.s,
+            "v": U256::from(v),
+            "chainId": transaction.chain_id().map(U256::from),
+            "accessList": transaction.access_list(),
+            "type": U256::from(tx_type),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_into_signed_transaction_legacy() {
+        // Given
+        let transaction = sign(Transaction::Legacy(TxLegacy {
+            chain_id: Some(1_263_227_476),
+            nonce: 1,
+            gas_price: 10,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(Address::with_last_byte(1)),
+            value: U256::from(1).into(),
+            input: Bytes::default(),
+        }));
+
+        // When
+        let converted = into_signed_transaction(to_rpc_transaction(&transaction)).unwrap();
+
+        // Then
+        assert_eq!(converted, transaction);
+    }
+
+    #[test]
+    fn test_into_signed_transaction_eip1559() {
+        // Given
+        let transaction = sign(Transaction::Eip1559(TxEip1559 {
+            chain_id: 1_263_227_476,
+            nonce: 2,
+            gas_limit: 100_000,
+            max_fee_per_gas: 20,
+            max_priority_fee_per_gas: 1,
+            to: TransactionKind::Create,
+            value: U256::ZERO.into(),
+            access_list: AccessList::default(),
+            input: Bytes::from(vec![0x60, 0x80]),
+        }));
+
+        // When
+        let converted = into_signed_transaction(to_rpc_transaction(&transaction)).unwrap();
+
+        // Then
+        assert_eq!(converted, transaction);
+    }
+
+    #[test]
+    fn test_into_signed_transaction_hash_mismatch() {
+        // Given
+        let transaction = sign(Transaction::Legacy(TxLegacy {
+            chain_id: Some(1_263_227_476),
+            nonce: 1,
+            gas_price: 10,
+            gas_limit: 21_000,
+            to: TransactionKind::Call(Address::with_last_byte(1)),
+            value: U256::from(1).into(),
+            input: Bytes::default(),
+        }));
+        let mut rpc_transaction = to_rpc_transaction(&transaction);
+        rpc_transaction.hash = B256::ZERO;
+
+        // When
+        let result = into_signed_transaction(rpc_transaction);
+
+        // Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_into_consensus_header() {
+        // Given
+        let header: RpcHeader = serde_json::from_value(json!({
+            "hash": B256::with_last_byte(1),
+            "parentHash": B256::with_last_byte(2),
+            "sha3Uncles": B256::ZERO,
+            "miner": Address::with_last_byte(3),
+            "stateRoot": B256::ZERO,
+            "transactionsRoot": B256::ZERO,
+            "receiptsRoot": B256::ZERO,
+            "logsBloom": reth_primitives::Bloom::ZERO,
+            "difficulty": U256::ZERO,
+            "number": U256::from(0x1234),
+            "gasLimit": U256::from(30_000_000),
+            "gasUsed": U256::from(21_000),
+            "timestamp": U256::from(1_700_000_000),
+            "extraData": "0x",
+            "mixHash": B256::ZERO,
+            "nonce": "0x0000000000000000",
+            "baseFeePerGas": U256::from(7),
+        }))
+        .unwrap();
+
+        // When
+        let header = into_consensus_header(header).unwrap();
+
+        // Then
+        assert_eq!(header.number, 0x1234);
+        assert_eq!(header.parent_hash, B256::with_last_byte(2));
+        assert_eq!(header.beneficiary, Address::with_last_byte(3));
+        assert_eq!(header.gas_limit, 30_000_000);
+        assert_eq!(header.gas_used, 21_000);
+        assert_eq!(header.base_fee_per_gas, Some(7));
+    }
+
+    #[test]
+    fn test_link_header() {
+        // Given
+        let mut parent = Header { number: 1, parent_hash: B256::with_last_byte(1), ..Default::default() };
+        let mut child = Header { number: 2, parent_hash: B256::with_last_byte(2), ..Default::default() };
+
+        // When
+        let parent_hash = link_header(&mut parent, None);
+        let child_hash = link_header(&mut child, Some(parent_hash));
+
+        // Then
+        // The first header keeps its parent, the next ones point to the re-encoded headers
+        assert_eq!(parent.parent_hash, B256::with_last_byte(1));
+        assert_eq!(parent_hash, parent.hash_slow());
+        assert_eq!(child.parent_hash, parent.hash_slow());
+        assert_eq!(child_hash, child.hash_slow());
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::eth_provider::provider::EthProviderResult;
+
+/// Default maximum number of Starknet blocks the indexer can lag behind before the RPC is
+/// reported as unhealthy.
+pub const DEFAULT_MAX_INDEXER_LAG: u64 = 10;
+
+/// Health of a dependency of the RPC, along with the highest block number it knows.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    pub healthy: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<EthProviderResult<Option<u64>>> for DependencyHealth {
+    fn from(result: EthProviderResult<Option<u64>>) -> Self {
+        match result {
+            Ok(block_number) => Self { healthy: true, block_number, error: None },
+            Err(err) => Self { healthy: false, block_number: None, error: Some(err.to_string()) },
+        }
+    }
+}
+
+/// Health of the dependencies of the provider: the Mongo database, with its highest
+/// indexed header, and the Starknet provider, with its latest block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProviderHealth {
+    pub mongo: DependencyHealth,
+    pub starknet: DependencyHealth,
+}
+
+/// Number of Starknet blocks which are not indexed in the database yet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IndexerHealth {
+    pub healthy: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lag: Option<u64>,
+    pub max_lag: u64,
+}
+
+/// Health report of the RPC, returned by `net_health` and `GET /ready`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub healthy: bool,
+    pub mongo: DependencyHealth,
+    pub starknet: DependencyHealth,
+    pub indexer: IndexerHealth,
+}
+
+impl HealthReport {
+    /// Returns the report of the provider health. The indexer is healthy if it lags at most
+    /// `max_lag` blocks behind the Starknet provider.
+    pub fn new(health: ProviderHealth, max_lag: u64) -> Self {
+        let ProviderHealth { mongo, starknet } = health;
+        let lag = mongo
+            .block_number
+            .zip(starknet.block_number)
+            .map(|(indexed_head, starknet_head)| starknet_head.saturating_sub(indexed_head));
+        let indexer = IndexerHealth { healthy: lag.is_some_and(|lag| lag <= max_lag), lag, max_lag };
+
+        Self { healthy: mongo.healthy && starknet.healthy && indexer.healthy, mongo, starknet, indexer }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth_provider::error::EthProviderError;
+
+    fn provider_health(indexed_head: Option<u64>, starknet_head: u64) -> ProviderHealth {
+        ProviderHealth {
+            mongo: Ok(indexed_head).into(),
+            starknet: DependencyHealth { healthy: true, block_number: Some(starknet_head), error: None },
+        }
+    }
+
+    #[test]
+    fn test_health_report() {
+        // When
+        let report = HealthReport::new(provider_health(Some(95), 100), 10);
+
+        // Then
+        assert!(report.healthy);
+        assert_eq!(report.indexer, IndexerHealth { healthy: true, lag: Some(5), max_lag: 10 });
+    }
+
+    #[test]
+    fn test_health_report_stale_indexer() {
+        // When
+        let report = HealthReport::new(provider_health(Some(80), 100), 10);
+
+        // Then
+        assert!(!report.healthy);
+        assert!(report.mongo.healthy);
+        assert_eq!(report.indexer, IndexerHealth { healthy: false, lag: Some(20), max_lag: 10 });
+    }
+
+    #[test]
+    fn test_health_report_empty_database() {
+        // When
+        let report = HealthReport::new(provider_health(None, 100), 10);
+
+        // Then
+        assert!(!report.healthy);
+        assert!(report.mongo.healthy);
+        assert_eq!(report.indexer.lag, None);
+    }
+
+    #[test]
+    fn test_health_report_unreachable_starknet() {
+        // Given
+        let health = ProviderHealth {
+            mongo: Ok(Some(100)).into(),
+            starknet: Err(EthProviderError::Other(eyre::eyre!("connection refused"))).into(),
+        };
+
+        // When
+        let report = HealthReport::new(health, 10);
+
+        // Then
+        assert!(!report.healthy);
+        assert_eq!(report.starknet.error.as_deref(), Some("connection refused"));
+        assert!(!report.indexer.healthy);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod balance;
 pub mod block;
+pub mod consensus;
 pub mod errors;
 pub mod felt;
+pub mod health;
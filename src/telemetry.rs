@@ -0,0 +1,47 @@
+//! Logs and traces of the Kakarot RPC. The traces are exported to an OpenTelemetry
+//! collector over OTLP when an endpoint is configured.
+use eyre::Result;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::Tracer;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Name of the service in the exported traces.
+const SERVICE_NAME: &str = "kakarot-rpc";
+
+/// Installs the global subscriber, logging the events filtered by `RUST_LOG` and
+/// exporting the spans to the OTLP endpoint, if any.
+///
+/// # Errors
+///
+/// Will return `Err` if `RUST_LOG` is invalid, if the OTLP exporter can't be built or
+/// if a global subscriber is already installed.
+pub fn init(otlp_endpoint: Option<&str>) -> Result<()> {
+    let filter = EnvFilter::try_from_default_env()?;
+    let otlp =
+        otlp_endpoint.map(otlp_tracer).transpose()?.map(|tracer| tracing_opentelemetry::layer().with_tracer(tracer));
+
+    tracing_subscriber::registry().with(filter).with(tracing_subscriber::fmt::layer()).with(otlp).try_init()?;
+    Ok(())
+}
+
+/// Flushes the spans which aren't exported yet.
+pub fn shutdown() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
+
+/// Returns a tracer exporting the spans in batches to the OTLP gRPC endpoint.
+fn otlp_tracer(endpoint: &str) -> Result<Tracer> {
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint))
+        .with_trace_config(
+            opentelemetry_sdk::trace::config()
+                .with_resource(Resource::new(vec![KeyValue::new("service.name", SERVICE_NAME)])),
+        )
+        .install_batch(runtime::Tokio)?;
+    Ok(tracer)
+}
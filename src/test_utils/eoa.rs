@@ -24,7 +24,7 @@ pub const TX_GAS_LIMIT: u64 = 5_000_000;
 #[async_trait]
 pub trait Eoa<P: Provider + Send + Sync> {
     fn starknet_address(&self) -> Result<FieldElement, eyre::Error> {
-        Ok(starknet_address(self.evm_address()?))
+        Ok(starknet_address(self.eth_provider().kakarot_deployment(), self.evm_address()?))
     }
     fn evm_address(&self) -> Result<Address, eyre::Error> {
         let wallet = LocalWallet::from_bytes(self.private_key().as_slice())?;
@@ -1,33 +1,63 @@
 use ethers::abi::Token;
+use lazy_static::lazy_static;
+use reth_primitives::Address;
 use rstest::*;
+use starknet_crypto::FieldElement;
 use tracing_subscriber::{filter, FmtSubscriber};
 
 use super::katana::Katana;
-use crate::test_utils::evm_contract::KakarotEvmContract;
+use crate::eth_provider::starknet::kakarot_core::starknet_address;
+use crate::test_utils::evm_contract::{EvmContract, KakarotEvmContract};
+
+lazy_static! {
+    /// The deployer of the contracts added to the test genesis. It differs from the
+    /// test EOA, so that contracts deployed by the tests don't collide with them.
+    pub static ref GENESIS_DEPLOYER: Address = Address::with_last_byte(0xde);
+}
+
+/// Returns the contracts added to the test genesis, with their constructor arguments.
+/// The contract at index `i` is created by the [GENESIS_DEPLOYER] at nonce `i`.
+pub fn genesis_contracts() -> Vec<(&'static str, Vec<Token>)> {
+    vec![
+        ("Counter", vec![]),
+        (
+            "ERC20",
+            vec![
+                Token::String("Test".into()),               // name
+                Token::String("TT".into()),                 // symbol
+                Token::Uint(ethers::types::U256::from(18)), // decimals
+            ],
+        ),
+    ]
+}
+
+/// Returns the contract with the given name, deployed in the test genesis.
+pub fn genesis_contract(katana: &Katana, name: &str) -> KakarotEvmContract {
+    let nonce = genesis_contracts()
+        .iter()
+        .position(|(contract_name, _)| *contract_name == name)
+        .unwrap_or_else(|| panic!("{name} isn't a genesis contract"));
+    let bytecode = <KakarotEvmContract as EvmContract>::load_contract_bytecode(name)
+        .unwrap_or_else(|_| panic!("Failed to load {name} bytecode"));
+
+    let evm_address = GENESIS_DEPLOYER.create(nonce as u64);
+    let starknet_address = starknet_address(katana.eth_provider().kakarot_deployment(), evm_address);
+    let evm_address =
+        FieldElement::from_byte_slice_be(evm_address.as_slice()).expect("Failed to convert address to field element");
+    KakarotEvmContract::new(bytecode, starknet_address, evm_address)
+}
 
 #[fixture]
 #[awt]
 pub async fn counter(#[future] katana: Katana) -> (Katana, KakarotEvmContract) {
-    let eoa = katana.eoa();
-    let contract = eoa.deploy_evm_contract("Counter", ()).await.expect("Failed to deploy Counter contract");
+    let contract = genesis_contract(&katana, "Counter");
     (katana, contract)
 }
 
 #[fixture]
 #[awt]
 pub async fn erc20(#[future] katana: Katana) -> (Katana, KakarotEvmContract) {
-    let eoa = katana.eoa();
-    let contract = eoa
-        .deploy_evm_contract(
-            "ERC20",
-            (
-                Token::String("Test".into()),               // name
-                Token::String("TT".into()),                 // symbol
-                Token::Uint(ethers::types::U256::from(18)), // decimals
-            ),
-        )
-        .await
-        .expect("Failed to deploy ERC20 contract");
+    let contract = genesis_contract(&katana, "ERC20");
     (katana, contract)
 }
 
@@ -1,131 +1,198 @@
 use std::collections::HashMap;
 
-use ef_testing::evm_sequencer::account::{AccountType, KakarotAccount};
-use ethers::types::U256 as EthersU256;
 use eyre::eyre;
-use katana_primitives::{
-    contract::ContractAddress,
-    genesis::json::{GenesisContractJson, GenesisJson},
-};
+use katana_primitives::genesis::json::GenesisJson;
 use reth_primitives::{Address, Bytes, B256, U256, U64};
 use serde::{Deserialize, Serialize};
-use starknet::core::utils::get_storage_var_address;
-use starknet_api::core::ClassHash;
+use serde_json::Value;
 use starknet_crypto::FieldElement;
 
 use super::katana::genesis::{KatanaGenesisBuilder, Loaded};
 
 /// Types from https://github.com/ethereum/go-ethereum/blob/master/core/genesis.go#L49C1-L58
 #[derive(Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct HiveGenesisConfig {
     pub config: Config,
+    #[serde(default)]
     pub coinbase: Address,
-    pub difficulty: U64,
+    #[serde(default)]
+    pub difficulty: U256,
+    #[serde(default)]
     pub extra_data: Bytes,
-    pub gas_limit: U64,
+    pub gas_limit: Option<U64>,
+    #[serde(default)]
     pub nonce: U64,
+    #[serde(default)]
     pub timestamp: U64,
+    pub mix_hash: Option<B256>,
+    pub number: Option<U64>,
+    pub gas_used: Option<U64>,
+    pub parent_hash: Option<B256>,
+    pub base_fee_per_gas: Option<U256>,
+    pub excess_blob_gas: Option<U64>,
+    pub blob_gas_used: Option<U64>,
     pub alloc: HashMap<Address, AccountInfo>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
+/// Types from https://github.com/ethereum/go-ethereum/blob/master/params/config.go
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
 pub struct Config {
-    pub chain_id: i128,
-    pub homestead_block: i128,
-    pub eip150_block: i128,
+    pub chain_id: u64,
+    pub homestead_block: Option<u64>,
+    pub dao_fork_block: Option<u64>,
+    #[serde(default)]
+    pub dao_fork_support: bool,
+    pub eip150_block: Option<u64>,
     pub eip150_hash: Option<B256>,
-    pub eip155_block: i128,
-    pub eip158_block: i128,
+    pub eip155_block: Option<u64>,
+    pub eip158_block: Option<u64>,
+    pub byzantium_block: Option<u64>,
+    pub constantinople_block: Option<u64>,
+    pub petersburg_block: Option<u64>,
+    pub istanbul_block: Option<u64>,
+    pub muir_glacier_block: Option<u64>,
+    pub berlin_block: Option<u64>,
+    pub london_block: Option<u64>,
+    pub arrow_glacier_block: Option<u64>,
+    pub gray_glacier_block: Option<u64>,
+    pub merge_netsplit_block: Option<u64>,
+    pub shanghai_time: Option<u64>,
+    pub cancun_time: Option<u64>,
+    pub prague_time: Option<u64>,
+    pub terminal_total_difficulty: Option<U256>,
+    #[serde(default)]
+    pub terminal_total_difficulty_passed: bool,
+    pub ethash: Option<Value>,
+    pub clique: Option<Value>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+impl Config {
+    /// Checks that the chain configuration can be run by Kakarot, which applies the same EVM
+    /// rules from genesis on and reports the chain id of the underlying Starknet chain, given
+    /// as `chain_id`. The forks Kakarot supports must either be activated at genesis or left
+    /// unset, the other forks must be left unset. All the violations are reported.
+    pub fn validate(&self, chain_id: u64) -> Result<(), eyre::Error> {
+        let mut errors = Vec::new();
+
+        if self.chain_id != chain_id {
+            errors.push(format!("chainId {}: Kakarot runs with the chain id {chain_id}", self.chain_id));
+        }
+
+        let supported_forks = [
+            ("homesteadBlock", self.homestead_block),
+            ("eip150Block", self.eip150_block),
+            ("eip155Block", self.eip155_block),
+            ("eip158Block", self.eip158_block),
+            ("byzantiumBlock", self.byzantium_block),
+            ("constantinopleBlock", self.constantinople_block),
+            ("petersburgBlock", self.petersburg_block),
+            ("istanbulBlock", self.istanbul_block),
+            ("muirGlacierBlock", self.muir_glacier_block),
+            ("berlinBlock", self.berlin_block),
+            ("londonBlock", self.london_block),
+            ("arrowGlacierBlock", self.arrow_glacier_block),
+            ("grayGlacierBlock", self.gray_glacier_block),
+            ("mergeNetsplitBlock", self.merge_netsplit_block),
+            ("shanghaiTime", self.shanghai_time),
+        ];
+        for (name, activation) in supported_forks {
+            if let Some(activation) = activation.filter(|activation| *activation != 0) {
+                errors.push(format!("{name} {activation}: Kakarot can't activate a fork after genesis"));
+            }
+        }
+
+        let unsupported_forks = [("cancunTime", self.cancun_time), ("pragueTime", self.prague_time)];
+        for (name, activation) in unsupported_forks {
+            if let Some(activation) = activation {
+                errors.push(format!("{name} {activation}: the fork isn't supported by Kakarot"));
+            }
+        }
+
+        if self.dao_fork_support {
+            errors.push("daoForkSupport: Kakarot doesn't apply the DAO fork state changes".to_string());
+        }
+        if let Some(ttd) = self.terminal_total_difficulty.filter(|ttd| *ttd != U256::ZERO) {
+            errors.push(format!(
+                "terminalTotalDifficulty {ttd}: Kakarot has no proof of work phase, the terminal total difficulty \
+                 must be 0"
+            ));
+        }
+        if self.clique.is_some() {
+            errors.push("clique: Kakarot doesn't run a consensus engine".to_string());
+        }
+
+        if !errors.is_empty() {
+            return Err(eyre!("Unsupported genesis configuration:\n{}", errors.join("\n")));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct AccountInfo {
     pub balance: U256,
     pub code: Option<Bytes>,
     pub storage: Option<HashMap<U256, U256>>,
+    pub nonce: Option<U64>,
 }
 
 impl HiveGenesisConfig {
+    /// Checks that the genesis can be run by Kakarot on a Starknet chain of id `chain_id`, see
+    /// [Config::validate]. The proof of work fields (difficulty, nonce and extra data) are ignored.
+    pub fn validate(&self, chain_id: u64) -> Result<(), eyre::Error> {
+        self.config.validate(chain_id)?;
+
+        if self.gas_used.is_some_and(|gas_used| gas_used != U64::ZERO) {
+            return Err(eyre!("Unsupported genesis gasUsed: the genesis block can't contain transactions"));
+        }
+        if self.parent_hash.is_some_and(|parent_hash| parent_hash != B256::ZERO) {
+            return Err(eyre!("Unsupported genesis parentHash: the genesis block can't have a parent"));
+        }
+        if self.excess_blob_gas.is_some() || self.blob_gas_used.is_some() {
+            return Err(eyre!("Unsupported genesis blob gas fields: Kakarot doesn't support blob transactions"));
+        }
+        Ok(())
+    }
+
     /// Convert the [HiveGenesisConfig] into a [GenesisJson] using an [KatanaGenesisBuilder]<[Loaded]>. The [Loaded]
     /// marker type indicates that the Kakarot contract classes need to have been loaded into the builder.
-    pub fn try_into_genesis_json(self, builder: KatanaGenesisBuilder<Loaded>) -> Result<GenesisJson, eyre::Error> {
-        let coinbase_address = FieldElement::from_byte_slice_be(self.coinbase.as_slice())?;
-        let builder = builder.with_kakarot(coinbase_address)?;
-
-        // Get the current state of the builder.
-        let kakarot_address = builder.cache_load("kakarot_address")?;
-        let proxy_class_hash = ClassHash(builder.proxy_class_hash()?.into());
-        let eoa_class_hash = ClassHash(builder.eoa_class_hash()?.into());
-        let contract_account_class_hash = ClassHash(builder.contract_account_class_hash()?.into());
-
-        // Fetch the contracts from the alloc field.
-        let mut additional_kakarot_storage = HashMap::new();
-        let mut fee_token_storage = HashMap::new();
-        let contracts = self
-            .alloc
-            .into_iter()
-            .map(|(address, info)| {
-                let evm_address = FieldElement::from_byte_slice_be(address.as_slice())?;
-                let starknet_address = builder.compute_starknet_address(evm_address)?.0;
-
-                // Store the mapping from EVM to Starknet address.
-                additional_kakarot_storage
-                    .insert(get_storage_var_address("evm_to_starknet_address", &[evm_address])?, starknet_address);
-
-                // Get the Kakarot account in order to have the account type and storage.
-                let code = info.code.unwrap_or_default();
-                let storage = info.storage.unwrap_or_default();
-                let storage: Vec<(U256, U256)> = storage.into_iter().collect();
-                let kakarot_account = KakarotAccount::new(&address, &code, U256::ZERO, &storage)?;
-
-                let account_type = kakarot_account.account_type();
-                let mut kakarot_account_storage: Vec<(FieldElement, FieldElement)> =
-                    kakarot_account.storage().iter().map(|(k, v)| ((*k.0.key()).into(), (*v).into())).collect();
-
-                // Add the implementation and the kakarot address to the storage.
-                let implementation_key = get_storage_var_address("_implementation", &[])?;
-                match account_type {
-                    AccountType::Contract => {
-                        kakarot_account_storage.append(&mut vec![
-                            (implementation_key, contract_account_class_hash.0.into()),
-                            (get_storage_var_address("Ownable_owner", &[])?, kakarot_address),
-                        ]);
-                    }
-                    AccountType::EOA => kakarot_account_storage.push((implementation_key, eoa_class_hash.0.into())),
-                    _ => unreachable!("Invalid account type"),
-                };
-                kakarot_account_storage.push((get_storage_var_address("kakarot_address", &[])?, kakarot_address));
-
-                let key = get_storage_var_address("ERC20_allowances", &[starknet_address, kakarot_address])?;
-                fee_token_storage.insert(key, FieldElement::from(u128::MAX));
-                fee_token_storage.insert(key + 1u8.into(), FieldElement::from(u128::MAX));
-
-                Ok((
-                    ContractAddress::new(starknet_address),
-                    GenesisContractJson {
-                        class: Some(proxy_class_hash.0.into()),
-                        balance: Some(EthersU256::from_big_endian(&info.balance.to_be_bytes::<32>())),
-                        nonce: None,
-                        storage: Some(kakarot_account_storage.into_iter().collect()),
-                    },
-                ))
-            })
-            .collect::<Result<HashMap<_, _>, eyre::Error>>()?;
-
-        // Build the builder
-        let kakarot_address = ContractAddress::new(kakarot_address);
-        let mut genesis = builder.build()?;
+    /// The coinbase, the block gas limit, the base fee and the mix hash (as the prev randao) are set
+    /// in the Kakarot contract storage. The genesis must be run on a Katana of id `chain_id`.
+    pub fn try_into_genesis_json(
+        self,
+        builder: KatanaGenesisBuilder<Loaded>,
+        chain_id: u64,
+    ) -> Result<GenesisJson, eyre::Error> {
+        self.validate(chain_id)?;
 
-        let kakarot_contract =
-            genesis.contracts.get_mut(&kakarot_address).ok_or(eyre!("Kakarot contract not found"))?;
-        kakarot_contract.storage.get_or_insert_with(HashMap::new).extend(additional_kakarot_storage);
+        let coinbase_address = FieldElement::from_byte_slice_be(self.coinbase.as_slice())?;
+        let mut builder = builder.with_kakarot(coinbase_address)?;
+        if let Some(gas_limit) = self.gas_limit {
+            builder = builder.with_block_gas_limit(gas_limit.to())?;
+        }
+        if let Some(base_fee) = self.base_fee_per_gas {
+            let base_fee = u128::try_from(base_fee).map_err(|_| eyre!("Unsupported baseFeePerGas {base_fee}"))?;
+            builder = builder.with_base_fee(base_fee.into())?;
+        }
+        if let Some(mix_hash) = self.mix_hash {
+            let prev_randao = FieldElement::from_bytes_be(&mix_hash.0)
+                .map_err(|_| eyre!("Unsupported mixHash {mix_hash}: the value doesn't fit in a felt"))?;
+            builder = builder.with_prev_randao(prev_randao)?;
+        }
 
-        genesis.fee_token.storage.get_or_insert_with(HashMap::new).extend(fee_token_storage);
+        // Add the accounts from the alloc field.
+        for (address, info) in self.alloc {
+            let code = info.code.unwrap_or_default();
+            let storage: Vec<(U256, U256)> = info.storage.unwrap_or_default().into_iter().collect();
+            let nonce = info.nonce.map(|nonce| nonce.to::<u64>()).unwrap_or_default();
+            builder = builder.with_evm_account(address, &code, &storage, nonce, info.balance)?;
+        }
 
-        // Add the contracts to the genesis.
-        genesis.contracts.extend(contracts);
+        let mut genesis = builder.build()?;
+        genesis.number = self.number.map(|number| number.to()).unwrap_or_default();
+        genesis.timestamp = self.timestamp.to();
 
         Ok(genesis)
     }
@@ -135,6 +202,10 @@ impl HiveGenesisConfig {
 mod tests {
     use lazy_static::lazy_static;
 
+    use ethers::types::U256 as EthersU256;
+    use katana_primitives::contract::ContractAddress;
+    use starknet::core::utils::get_storage_var_address;
+
     use crate::{eth_provider::utils::split_u256, test_utils::katana::genesis::Initialized};
 
     use super::*;
@@ -151,8 +222,10 @@ mod tests {
             KatanaGenesisBuilder::default().load_classes(ROOT.join("lib/kakarot/build"));
         static ref GENESIS_BUILDER: KatanaGenesisBuilder<Initialized> =
             GENESIS_BUILDER_LOADED.clone().with_kakarot(FieldElement::ZERO).unwrap();
-        static ref GENESIS: GenesisJson =
-            HIVE_GENESIS.clone().try_into_genesis_json(GENESIS_BUILDER_LOADED.clone()).unwrap();
+        static ref GENESIS: GenesisJson = HIVE_GENESIS
+            .clone()
+            .try_into_genesis_json(GENESIS_BUILDER_LOADED.clone(), HIVE_GENESIS.config.chain_id)
+            .unwrap();
     }
 
     #[test]
@@ -186,4 +259,71 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_genesis_kakarot_storage() {
+        // Given
+        let kakarot_address = ContractAddress::new(GENESIS_BUILDER.cache_load("kakarot_address").unwrap());
+        let storage = GENESIS.contracts.get(&kakarot_address).unwrap().storage.as_ref().unwrap();
+
+        // Then
+        let block_gas_limit = storage.get(&get_storage_var_address("block_gas_limit", &[]).unwrap()).unwrap();
+        assert_eq!(*block_gas_limit, FieldElement::from(HIVE_GENESIS.gas_limit.unwrap().to::<u64>()));
+        assert_eq!(GENESIS.timestamp, HIVE_GENESIS.timestamp.to::<u64>());
+    }
+
+    #[test]
+    fn test_validate_forks_at_genesis() {
+        // Given
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "chainId": 1337,
+            "homesteadBlock": 0,
+            "eip150Block": 0,
+            "eip155Block": 0,
+            "eip158Block": 0,
+            "byzantiumBlock": 0,
+            "londonBlock": 0,
+            "shanghaiTime": 0,
+            "terminalTotalDifficulty": 0,
+            "terminalTotalDifficultyPassed": true,
+            "ethash": {}
+        }))
+        .unwrap();
+
+        // When
+        let result = config.validate(1337);
+
+        // Then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_validate_unsupported_config() {
+        // Given
+        let config = Config {
+            chain_id: 1,
+            london_block: Some(10),
+            cancun_time: Some(0),
+            terminal_total_difficulty: Some(U256::from(131_072)),
+            ..Default::default()
+        };
+
+        // When
+        let err = config.validate(1_802_203_764).unwrap_err().to_string();
+
+        // Then
+        assert!(err.contains("chainId 1: Kakarot runs with the chain id 1802203764"));
+        assert!(err.contains("londonBlock 10"));
+        assert!(err.contains("cancunTime 0"));
+        assert!(err.contains("terminalTotalDifficulty 131072"));
+    }
+
+    #[test]
+    fn test_unknown_config_field() {
+        // When
+        let result = serde_json::from_value::<Config>(serde_json::json!({"chainId": 1, "verkleTime": 0}));
+
+        // Then
+        assert!(result.is_err());
+    }
 }
@@ -5,9 +5,12 @@ use std::path::PathBuf;
 
 use cairo_lang_starknet::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet::contract_class::ContractClass;
+use ef_testing::evm_sequencer::account::{AccountType, KakarotAccount};
+use ethers::abi::Tokenize;
 use ethers::signers::LocalWallet;
 use ethers::signers::Signer;
 use ethers::types::U256;
+use ethers_solc::artifacts::CompactContractBytecode;
 use eyre::{eyre, Result};
 use katana_primitives::block::GasPrices;
 use katana_primitives::contract::{StorageKey, StorageValue};
@@ -22,7 +25,10 @@ use katana_primitives::{
 };
 use lazy_static::lazy_static;
 use rayon::prelude::*;
-use reth_primitives::B256;
+use reth_primitives::{Address, Bytes, B256, U256 as EvmU256};
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{AccountInfo, CreateScheme, ResultAndState, TransactTo};
+use revm::Evm;
 use serde::Serialize;
 use serde_json::Value;
 use serde_with::serde_as;
@@ -32,6 +38,9 @@ use starknet::core::types::FieldElement;
 use starknet::core::utils::{get_contract_address, get_storage_var_address, get_udc_deployed_address, UdcUniqueness};
 use walkdir::WalkDir;
 
+use crate::test_utils::eoa::TX_GAS_LIMIT;
+use crate::test_utils::evm_contract::{EvmContract, KakarotEvmContract};
+
 lazy_static! {
     static ref SALT: FieldElement = FieldElement::from_bytes_be(&[0u8; 32]).unwrap();
 }
@@ -298,6 +307,95 @@ impl KatanaGenesisBuilder<Initialized> {
         Ok(self)
     }
 
+    /// Add an EVM account to the genesis, deployed at the Starknet address computed for the EVM address.
+    /// The account is an EOA if its code is empty, a contract account otherwise.
+    pub fn with_evm_account(
+        mut self,
+        address: Address,
+        code: &Bytes,
+        storage: &[(EvmU256, EvmU256)],
+        nonce: u64,
+        balance: EvmU256,
+    ) -> Result<Self> {
+        let evm_address = FieldElement::from_byte_slice_be(address.as_slice())?;
+        let starknet_address = self.compute_starknet_address(evm_address)?;
+        let kakarot_address = self.cache_load("kakarot_address")?;
+
+        // Get the Kakarot account in order to have the account type and storage.
+        let kakarot_account = KakarotAccount::new(&address, code, EvmU256::from(nonce), storage)?;
+        let mut account_storage: HashMap<FieldElement, FieldElement> =
+            kakarot_account.storage().iter().map(|(k, v)| ((*k.0.key()).into(), (*v).into())).collect();
+
+        // Add the implementation and the kakarot address to the storage.
+        let (implementation, is_eoa) = match kakarot_account.account_type() {
+            AccountType::Contract => {
+                account_storage.insert(storage_addr("Ownable_owner")?, kakarot_address);
+                (self.contract_account_class_hash()?, false)
+            }
+            AccountType::EOA => (self.eoa_class_hash()?, true),
+            _ => return Err(eyre!("Invalid account type")),
+        };
+        account_storage.insert(storage_addr("_implementation")?, implementation);
+        account_storage.insert(storage_addr("kakarot_address")?, kakarot_address);
+
+        let account = GenesisContractJson {
+            class: Some(self.proxy_class_hash()?),
+            balance: Some(U256::from_big_endian(&balance.to_be_bytes::<32>())),
+            // The nonce of contract accounts is kept in their storage, the
+            // nonce of EOAs is their Starknet nonce.
+            nonce: (is_eoa && nonce != 0).then(|| nonce.into()),
+            storage: Some(account_storage),
+        };
+        self.contracts.insert(starknet_address, account);
+
+        // Set the allowance for the account to the Kakarot contract.
+        let key = get_storage_var_address("ERC20_allowances", &[*starknet_address, kakarot_address])?;
+        let storage =
+            [(key, FieldElement::from(u128::MAX)), (key + 1u8.into(), FieldElement::from(u128::MAX))].into_iter();
+        self.fee_token_storage.extend(storage);
+
+        // Write the address to the Kakarot evm to starknet mapping
+        let mapping_key = get_storage_var_address("evm_to_starknet_address", &[evm_address])?;
+        self.set_kakarot_storage_at(mapping_key, starknet_address.0)?;
+
+        Ok(self)
+    }
+
+    /// Add an EVM contract to the genesis, with the given runtime code and storage.
+    /// Following EIP-161, the nonce of the contract is set to 1.
+    pub fn with_evm_contract(self, address: Address, code: Bytes, storage: HashMap<EvmU256, EvmU256>) -> Result<Self> {
+        let storage = storage.into_iter().collect::<Vec<_>>();
+        self.with_evm_account(address, &code, &storage, 1, EvmU256::ZERO)
+    }
+
+    /// Add the contract of the Foundry artifact to the genesis, as if it was created by the deployer
+    /// at the given nonce, i.e. at `deployer.create(nonce)`. The constructor is run offline and the
+    /// resulting runtime code and storage are written to the genesis, along with the contracts
+    /// created by the constructor, see [`create_foundry_artifact`].
+    pub fn with_foundry_artifact<T: Tokenize>(
+        self,
+        artifact: &CompactContractBytecode,
+        constructor_args: T,
+        deployer: Address,
+        nonce: u64,
+    ) -> Result<Self> {
+        self.with_created_contracts(create_foundry_artifact(artifact, constructor_args, deployer, nonce)?)
+    }
+
+    /// Add the contracts created by a constructor run offline to the genesis.
+    pub fn with_created_contracts(mut self, contracts: Vec<CreatedContract>) -> Result<Self> {
+        for contract in contracts {
+            self = self.with_evm_account(
+                contract.address,
+                &contract.code,
+                &contract.storage,
+                contract.nonce,
+                contract.balance,
+            )?;
+        }
+        Ok(self)
+    }
+
     /// Fund the starknet address deployed for the evm address of the passed private key
     /// with the given amount of tokens.
     pub fn fund(mut self, pk: B256, amount: U256) -> Result<Self> {
@@ -319,6 +417,35 @@ impl KatanaGenesisBuilder<Initialized> {
         Ok(self)
     }
 
+    /// Set the block gas limit of the Kakarot contract.
+    pub fn with_block_gas_limit(mut self, block_gas_limit: u64) -> Result<Self> {
+        self.set_kakarot_storage("block_gas_limit", block_gas_limit.into())?;
+        Ok(self)
+    }
+
+    /// Set the base fee of the Kakarot contract.
+    pub fn with_base_fee(mut self, base_fee: FieldElement) -> Result<Self> {
+        self.set_kakarot_storage("base_fee", base_fee)?;
+        Ok(self)
+    }
+
+    /// Set the prev randao of the Kakarot contract.
+    pub fn with_prev_randao(mut self, prev_randao: FieldElement) -> Result<Self> {
+        self.set_kakarot_storage("prev_randao", prev_randao)?;
+        Ok(self)
+    }
+
+    fn set_kakarot_storage(&mut self, var_name: &str, value: FieldElement) -> Result<()> {
+        self.set_kakarot_storage_at(storage_addr(var_name)?, value)
+    }
+
+    fn set_kakarot_storage_at(&mut self, key: FieldElement, value: FieldElement) -> Result<()> {
+        let kakarot_address = ContractAddress::new(self.cache_load("kakarot_address")?);
+        let kakarot_contract = self.contracts.get_mut(&kakarot_address).ok_or(eyre!("Kakarot contract missing"))?;
+        kakarot_contract.storage.get_or_insert_with(HashMap::new).insert(key, value);
+        Ok(())
+    }
+
     /// Consume the [KatanaGenesisBuilder] and returns the corresponding [GenesisJson].
     pub fn build(self) -> Result<GenesisJson> {
         Ok(GenesisJson {
@@ -394,3 +521,107 @@ fn compute_class_hash(class: &Value) -> Result<FieldElement> {
 fn storage_addr(var_name: &str) -> Result<FieldElement> {
     Ok(get_storage_var_address(var_name, &[])?)
 }
+
+/// A contract created by running a constructor offline.
+#[derive(Debug)]
+pub struct CreatedContract {
+    pub address: Address,
+    pub code: Bytes,
+    pub storage: Vec<(EvmU256, EvmU256)>,
+    pub nonce: u64,
+    pub balance: EvmU256,
+}
+
+/// Runs the constructor of the Foundry artifact offline, as if the contract was created by the
+/// deployer at the given nonce, and returns the contracts created by the constructor.
+pub fn create_foundry_artifact<T: Tokenize>(
+    artifact: &CompactContractBytecode,
+    constructor_args: T,
+    deployer: Address,
+    nonce: u64,
+) -> Result<Vec<CreatedContract>> {
+    let transaction =
+        <KakarotEvmContract as EvmContract>::prepare_create_transaction(artifact, constructor_args, nonce, 0)?;
+    run_constructor(transaction.input().clone(), deployer, nonce)
+}
+
+/// Runs the init code offline on an empty state, as a create transaction sent by the deployer
+/// at the given nonce, and returns the contracts created by the transaction.
+fn run_constructor(init_code: Bytes, deployer: Address, nonce: u64) -> Result<Vec<CreatedContract>> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(deployer, AccountInfo { nonce, ..Default::default() });
+
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .modify_tx_env(|tx| {
+            tx.caller = deployer;
+            tx.transact_to = TransactTo::Create(CreateScheme::Create);
+            tx.data = init_code.clone();
+            tx.gas_limit = TX_GAS_LIMIT;
+        })
+        .build();
+    let ResultAndState { result, state } =
+        evm.transact().map_err(|err| eyre!("Failed to run the constructor: {err:?}"))?;
+    if !result.is_success() {
+        return Err(eyre!("Constructor failed: {result:?}"));
+    }
+
+    let contracts = state
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let code = account.info.code?.original_bytes();
+            if code.is_empty() {
+                return None;
+            }
+            let storage = account
+                .storage
+                .into_iter()
+                .map(|(key, slot)| (key, slot.present_value))
+                .filter(|(_, value)| *value != EvmU256::ZERO)
+                .collect();
+            Some(CreatedContract { address, code, storage, nonce: account.info.nonce, balance: account.info.balance })
+        })
+        .collect();
+    Ok(contracts)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_run_constructor() {
+        // Given
+        // Runtime code returning 42.
+        let runtime_code = "602a60005260206000f3";
+        // Init code storing 42 at slot 0 and returning the runtime code.
+        let init_code = Bytes::from_str(&format!("0x602a600055600a6011600039600a6000f3{runtime_code}")).unwrap();
+        let deployer = Address::with_last_byte(1);
+
+        // When
+        let contracts = run_constructor(init_code, deployer, 3).unwrap();
+
+        // Then
+        assert_eq!(contracts.len(), 1);
+        let contract = &contracts[0];
+        assert_eq!(contract.address, deployer.create(3));
+        assert_eq!(contract.code, Bytes::from_str(runtime_code).unwrap());
+        assert_eq!(contract.storage, vec![(EvmU256::ZERO, EvmU256::from(42))]);
+        assert_eq!(contract.nonce, 1);
+    }
+
+    #[test]
+    fn test_run_constructor_revert() {
+        // Given
+        // Init code reverting with empty data.
+        let init_code = Bytes::from_str("0x60006000fd").unwrap();
+
+        // When
+        let result = run_constructor(init_code, Address::with_last_byte(1), 0);
+
+        // Then
+        assert!(result.is_err());
+    }
+}
@@ -1,4 +1,5 @@
 pub mod genesis;
+pub mod predeploys;
 
 use std::path::Path;
 use std::str::FromStr as _;
@@ -10,10 +11,13 @@ use katana_primitives::chain::ChainId;
 use katana_primitives::genesis::json::GenesisJson;
 use katana_primitives::genesis::Genesis;
 use reth_primitives::B256;
+use serde_json::Value;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
+use starknet_crypto::FieldElement;
 
 use crate::eth_provider::provider::EthDataProvider;
+use crate::eth_provider::starknet::kakarot_core::KakarotDeployment;
 use crate::test_utils::eoa::KakarotEOA;
 
 use super::mongo::mock_database;
@@ -24,6 +28,27 @@ fn load_genesis() -> Genesis {
     Genesis::try_from(genesis_json).expect("Failed to convert GenesisJson to Genesis")
 }
 
+/// Returns the Kakarot deployment of the genesis, read from its manifest.
+fn load_kakarot_deployment() -> KakarotDeployment {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(".katana/manifest.json");
+    let manifest = std::fs::read_to_string(path).expect("Failed to load manifest.json, run `make katana-genesis`");
+    let manifest: Value = serde_json::from_str(&manifest).expect("Failed to parse manifest.json");
+    let field_element = |pointer: &str| {
+        manifest
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .and_then(|value| FieldElement::from_hex_be(value).ok())
+            .unwrap_or_else(|| panic!("Missing {pointer} in manifest.json"))
+    };
+
+    KakarotDeployment::new(
+        field_element("/deployments/kakarot_address"),
+        field_element("/declarations/proxy"),
+        field_element("/declarations/externally_owned_account"),
+        field_element("/declarations/contract_account"),
+    )
+}
+
 /// Returns a `StarknetConfig` instance customized for Kakarot.
 /// If `with_dumped_state` is true, the config will be initialized with the dumped state.
 pub fn katana_config() -> StarknetConfig {
@@ -68,7 +93,7 @@ impl Katana {
 
         // Create a Kakarot client
         let database = mock_database().await;
-        let eth_provider = Arc::new(EthDataProvider::new(database, starknet_provider));
+        let eth_provider = Arc::new(EthDataProvider::new(database, starknet_provider, load_kakarot_deployment()));
 
         let eoa = KakarotEOA::new(pk, eth_provider);
 
@@ -60,7 +60,7 @@ use crate::eth_rpc::run_server;
 pub async fn start_kakarot_rpc_server(katana: &Katana) -> Result<(SocketAddr, ServerHandle), eyre::Report> {
     // Create and run Kakarot RPC module.
     let kakarot_rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module()?;
-    let rpc_config = RPCConfig::from_env()?;
+    let rpc_config = RPCConfig::new("127.0.0.1:0".to_string());
     let (server_addr, server_handle) = run_server(kakarot_rpc_module, rpc_config).await?;
 
     Ok((server_addr, server_handle))
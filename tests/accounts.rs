@@ -0,0 +1,118 @@
+#![cfg(feature = "testing")]
+use std::sync::Arc;
+
+use alloy_rlp::Decodable;
+use kakarot_rpc::accounts::Keystore;
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::eth_rpc::api::eth_api::EthApiServer;
+use kakarot_rpc::eth_rpc::servers::eth_rpc::KakarotEthRpc;
+use kakarot_rpc::test_utils::eoa::Eoa as _;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use reth_primitives::{Address, BlockNumberOrTag, TransactionSigned, U256};
+use reth_rpc_types::TransactionRequest;
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_accounts(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eoa = katana.eoa();
+    let keystore = Keystore::from_private_keys([eoa.private_key()]).unwrap();
+    let eth_rpc = KakarotEthRpc::new(katana.eth_provider()).with_signer(Arc::new(keystore.into()));
+
+    // When
+    let accounts = eth_rpc.accounts().await.unwrap();
+
+    // Then
+    assert_eq!(accounts, vec![eoa.evm_address().unwrap()]);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_accounts_without_keystore(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_rpc = KakarotEthRpc::new(katana.eth_provider());
+
+    // When
+    let accounts = eth_rpc.accounts().await.unwrap();
+    let signature = eth_rpc.sign(katana.eoa().evm_address().unwrap(), vec![1, 2, 3].into()).await;
+
+    // Then
+    assert!(accounts.is_empty());
+    assert!(signature.is_err());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sign_transaction(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eoa = katana.eoa();
+    let from = eoa.evm_address().unwrap();
+    let keystore = Keystore::from_private_keys([eoa.private_key()]).unwrap();
+    let eth_rpc = KakarotEthRpc::new(katana.eth_provider()).with_signer(Arc::new(keystore.into()));
+
+    // When
+    let request = TransactionRequest {
+        from: Some(from),
+        to: Some(Address::with_last_byte(1)),
+        value: Some(U256::from(1)),
+        gas: Some(U256::from(21_000)),
+        ..Default::default()
+    };
+    let bytes = eth_rpc.sign_transaction(request).await.unwrap();
+
+    // Then
+    let transaction = TransactionSigned::decode(&mut bytes.as_ref()).unwrap();
+    let nonce = katana.eth_provider().transaction_count(from, None).await.unwrap();
+    let latest = katana.eth_provider().block_by_number(BlockNumberOrTag::Latest, false).await.unwrap().unwrap();
+    let base_fee = latest.header.base_fee_per_gas.unwrap_or_default();
+    let tip = katana.eth_provider().max_priority_fee_per_gas().await.unwrap();
+    assert_eq!(transaction.recover_signer(), Some(from));
+    assert_eq!(U256::from(transaction.nonce()), nonce);
+    assert_eq!(transaction.gas_limit(), 21_000);
+    // The max fee leaves room for the base fee of the latest block to double
+    assert_eq!(U256::from(transaction.max_fee_per_gas()), base_fee * U256::from(2) + tip);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sign_transaction_unknown_account(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let keystore = Keystore::from_private_keys([katana.eoa().private_key()]).unwrap();
+    let eth_rpc = KakarotEthRpc::new(katana.eth_provider()).with_signer(Arc::new(keystore.into()));
+
+    // When
+    let request = TransactionRequest { from: Some(Address::with_last_byte(1)), ..Default::default() };
+    let result = eth_rpc.sign_transaction(request).await;
+
+    // Then
+    assert!(result.is_err());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_send_transaction(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eoa = katana.eoa();
+    let keystore = Keystore::from_private_keys([eoa.private_key()]).unwrap();
+    let eth_rpc = KakarotEthRpc::new(katana.eth_provider()).with_signer(Arc::new(keystore.into()));
+
+    // When
+    let request = TransactionRequest {
+        from: Some(eoa.evm_address().unwrap()),
+        to: Some(Address::with_last_byte(1)),
+        value: Some(U256::from(1)),
+        gas: Some(U256::from(21_000)),
+        ..Default::default()
+    };
+    let hash = eth_rpc.send_transaction(request).await.unwrap();
+
+    // Then
+    assert!(!hash.is_zero());
+}
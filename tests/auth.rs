@@ -0,0 +1,92 @@
+#![cfg(feature = "testing")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use jsonwebtoken::{EncodingKey, Header};
+use kakarot_rpc::eth_provider::error::EthRpcErrorCode;
+use kakarot_rpc::eth_rpc::config::RPCConfig;
+use kakarot_rpc::eth_rpc::rpc::KakarotRpcModuleBuilder;
+use kakarot_rpc::eth_rpc::run_server;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use rstest::*;
+use serde_json::{json, Value};
+
+const JWT_SECRET: [u8; 32] = [42; 32];
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auth(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let keys_file = std::env::temp_dir().join(format!("kakarot-rpc-auth-{}.toml", std::process::id()));
+    std::fs::write(
+        &keys_file,
+        format!(
+            r#"
+            [[clients]]
+            name = "partner"
+            api_key = "partner-key"
+            namespaces = ["net"]
+
+            [[clients]]
+            name = "sequencer"
+            jwt_secret = "{}"
+            "#,
+            hex::encode(JWT_SECRET)
+        ),
+    )
+    .unwrap();
+    let rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().unwrap();
+    let rpc_config = RPCConfig::new("127.0.0.1:0".to_string()).with_auth_keys_file(keys_file.clone());
+    let (server_addr, server_handle) = run_server(rpc_module, rpc_config).await.expect("Failed to start the server");
+
+    let iat = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let jwt = jsonwebtoken::encode(&Header::default(), &json!({ "iat": iat }), &EncodingKey::from_secret(&JWT_SECRET))
+        .unwrap();
+    let client = reqwest::Client::new();
+    let call = |path: &str, method: &str, header: Option<(&'static str, String)>| {
+        let mut request = client
+            .post(format!("http://{server_addr}{path}"))
+            .header("Content-Type", "application/json")
+            .body(json!({"jsonrpc": "2.0", "method": method, "params": [], "id": 1}).to_string());
+        if let Some((name, value)) = header {
+            request = request.header(name, value);
+        }
+        request.send()
+    };
+
+    // When
+    let anonymous = call("/", "net_version", None).await.unwrap();
+    let invalid_key = call("/", "net_version", Some(("X-Api-Key", "unknown".to_string()))).await.unwrap();
+    let header_key = call("/", "net_version", Some(("X-Api-Key", "partner-key".to_string()))).await.unwrap();
+    let path_key = call("/partner-key", "net_version", None).await.unwrap();
+    let denied_namespace = call("/partner-key", "eth_chainId", None).await.unwrap();
+    let jwt = call("/", "eth_chainId", Some(("Authorization", format!("Bearer {jwt}")))).await.unwrap();
+    let health = client.get(format!("http://{server_addr}/health")).send().await.unwrap();
+    let websocket = client
+        .get(format!("http://{server_addr}/"))
+        .header("X-Api-Key", "partner-key")
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+        .send()
+        .await
+        .unwrap();
+    std::fs::remove_file(&keys_file).unwrap();
+
+    // Then
+    let body = |response: reqwest::Response| async move {
+        serde_json::from_str::<Value>(&response.text().await.expect("Failed to get response body")).unwrap()
+    };
+    assert_eq!(anonymous.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert_eq!(invalid_key.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert!(body(header_key).await["result"].is_string());
+    assert!(body(path_key).await["result"].is_string());
+    assert_eq!(body(denied_namespace).await["error"]["code"], json!(EthRpcErrorCode::MethodNotFound as i32));
+    assert!(body(jwt).await["result"].is_string());
+    assert_eq!(health.status(), reqwest::StatusCode::OK);
+    // The WebSocket connections would escape the namespaces of the client
+    assert_eq!(websocket.status(), reqwest::StatusCode::FORBIDDEN);
+    drop(server_handle);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature = "testing")]
+use kakarot_rpc::eth_provider::cache::{CacheConfig, CachingProvider};
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::metrics::{CACHE_HITS, CACHE_MISSES};
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::mongo::{BLOCK_HASH, BLOCK_NUMBER};
+use reth_primitives::{BlockId, BlockNumberOrTag};
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cache_final_blocks(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider =
+        CachingProvider::new(katana.eth_provider(), CacheConfig { finality_depth: 0, ..Default::default() });
+    let hits = || CACHE_HITS.with_label_values(&["blocks"]).get();
+    let misses = || CACHE_MISSES.with_label_values(&["blocks"]).get();
+    let (hits_before, misses_before) = (hits(), misses());
+
+    // When
+    let block = eth_provider.block_by_number(BlockNumberOrTag::Number(*BLOCK_NUMBER), true).await.unwrap();
+    let cached_by_number = eth_provider.block_by_number(BlockNumberOrTag::Number(*BLOCK_NUMBER), true).await.unwrap();
+    let cached_by_hash = eth_provider.block_by_hash(*BLOCK_HASH, true).await.unwrap();
+
+    // Then
+    assert!(block.is_some());
+    assert_eq!(cached_by_number, block);
+    assert_eq!(cached_by_hash, block);
+    assert_eq!(misses() - misses_before, 1);
+    assert_eq!(hits() - hits_before, 2);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cache_skips_non_final_blocks(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider =
+        CachingProvider::new(katana.eth_provider(), CacheConfig { finality_depth: u64::MAX, ..Default::default() });
+    let block_id = Some(BlockId::Number(BlockNumberOrTag::Number(*BLOCK_NUMBER)));
+    let misses = || CACHE_MISSES.with_label_values(&["block_receipts"]).get();
+    let misses_before = misses();
+
+    // When
+    let receipts = eth_provider.block_receipts(block_id).await.unwrap();
+    let uncached_receipts = eth_provider.block_receipts(block_id).await.unwrap();
+
+    // Then
+    // Only the genesis block is at or below the finalized block, the receipts are fetched twice
+    assert!(receipts.is_some());
+    assert_eq!(uncached_receipts, receipts);
+    assert_eq!(misses() - misses_before, 2);
+}
@@ -1,21 +1,36 @@
 #![cfg(feature = "testing")]
 use std::cmp::min;
+use std::convert::Infallible;
+use std::net::SocketAddr;
 use std::str::FromStr;
-
-use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use std::sync::{Arc, Mutex};
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server};
+
+use kakarot_rpc::eth_provider::error::EthProviderError;
+use kakarot_rpc::eth_provider::gas_oracle::GasPriceOracleConfig;
+use kakarot_rpc::eth_provider::provider::{EthDataProvider, EthereumProvider};
+use kakarot_rpc::eth_provider::starknet::failover::{FailoverConfig, FailoverTransport};
+use kakarot_rpc::eth_provider::starknet::kakarot_core::starknet_address;
+use kakarot_rpc::eth_provider::starknet::proof::{BinaryNode, EdgeNode, EdgePath, ProofNode, StarknetProofClient};
+use kakarot_rpc::eth_provider::utils::split_u256;
 use kakarot_rpc::models::felt::Felt252Wrapper;
 use kakarot_rpc::test_utils::eoa::Eoa as _;
 use kakarot_rpc::test_utils::evm_contract::EvmContract;
 use kakarot_rpc::test_utils::fixtures::{counter, katana, setup};
-use kakarot_rpc::test_utils::mongo::{BLOCK_HASH, BLOCK_NUMBER};
+use kakarot_rpc::test_utils::mongo::{mock_database, BLOCK_HASH, BLOCK_NUMBER};
 use kakarot_rpc::test_utils::{evm_contract::KakarotEvmContract, katana::Katana};
 use reth_rpc_types::request::TransactionInput;
 use reth_rpc_types::{JsonStorageKey, RpcBlockHash, TransactionRequest, U64HexOrNumber};
 use rstest::*;
 
 use reth_primitives::{Address, BlockNumberOrTag, Bytes, B256, U256, U64};
+use serde_json::{json, Value};
 use starknet::core::types::BlockTag;
+use starknet::core::utils::get_storage_var_address;
 use starknet_crypto::FieldElement;
+use url::Url;
 
 #[rstest]
 #[awt]
@@ -48,6 +63,21 @@ async fn test_chain_id(#[future] katana: Katana, _setup: ()) {
     assert_eq!(chain_id, U64::from(0x6b616b6174657374u64));
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_coinbase(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+
+    // When
+    let coinbase = eth_provider.coinbase().await.unwrap();
+
+    // Then
+    // The coinbase is set to 0x12345 in the Katana genesis.
+    assert_eq!(coinbase, Address::from_str("0x0000000000000000000000000000000000012345").unwrap());
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -278,6 +308,69 @@ async fn test_fee_history(#[future] katana: Katana, _setup: ()) {
     assert_eq!(fee_history.oldest_block, U256::ZERO);
 }
 
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_history_reward_percentiles(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let reward_percentiles = vec![10., 50., 90.];
+
+    // When
+    let fee_history = eth_provider
+        .fee_history(
+            U64HexOrNumber::from(1u64),
+            BlockNumberOrTag::Number(*BLOCK_NUMBER),
+            Some(reward_percentiles.clone()),
+        )
+        .await
+        .unwrap();
+
+    // Then
+    // The mocked receipts all have an effective gas price of 0, below the base fee of the block.
+    let reward = fee_history.reward.unwrap();
+    assert_eq!(reward.len(), 1);
+    assert_eq!(reward[0], vec![U256::ZERO; reward_percentiles.len()]);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fee_history_invalid_reward_percentiles(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+
+    // When
+    let decreasing = eth_provider
+        .fee_history(U64HexOrNumber::from(1u64), BlockNumberOrTag::Number(*BLOCK_NUMBER), Some(vec![50., 10.]))
+        .await;
+    let out_of_range = eth_provider
+        .fee_history(U64HexOrNumber::from(1u64), BlockNumberOrTag::Number(*BLOCK_NUMBER), Some(vec![10., 101.]))
+        .await;
+
+    // Then
+    assert!(decreasing.is_err());
+    assert!(out_of_range.is_err());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_max_priority_fee_per_gas(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+
+    // When
+    let max_priority_fee_per_gas = eth_provider.max_priority_fee_per_gas().await.unwrap();
+    let gas_price = eth_provider.gas_price().await.unwrap();
+
+    // Then
+    // The mocked receipts all have an effective gas price of 0, so the oracle
+    // returns the default minimum priority fee.
+    assert_eq!(max_priority_fee_per_gas, GasPriceOracleConfig::default().min_priority_fee);
+    assert!(gas_price >= max_priority_fee_per_gas);
+}
+
 #[rstest]
 #[awt]
 #[tokio::test(flavor = "multi_thread")]
@@ -390,3 +483,108 @@ async fn test_to_starknet_block_id(#[future] katana: Katana, _setup: ()) {
     assert_eq!(some_starknet_block_number, starknet::core::types::BlockId::Tag(BlockTag::Pending));
     assert!(unknown_starknet_block_number.is_err());
 }
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_proof_without_proof_client(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+    let eoa = katana.eoa();
+
+    // When
+    let proof = eth_provider.get_proof(eoa.evm_address().unwrap(), vec![B256::ZERO], None).await;
+
+    // Then
+    assert!(matches!(proof, Err(EthProviderError::MethodNotSupported(_))));
+}
+
+/// Mock Pathfinder node answering `pathfinder_getProof` with the given result.
+/// Returns its URL and the params of the last request.
+fn mock_proof_server(result: Value) -> (Url, Arc<Mutex<Value>>) {
+    let params = Arc::new(Mutex::new(Value::Null));
+    let last_params = params.clone();
+    let make_service = make_service_fn(move |_| {
+        let (result, params) = (result.clone(), params.clone());
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+                let (result, params) = (result.clone(), params.clone());
+                async move {
+                    let body = hyper::body::to_bytes(request.into_body()).await.unwrap();
+                    let request: Value = serde_json::from_slice(&body).unwrap();
+                    assert_eq!(request["method"], "pathfinder_getProof");
+                    *params.lock().unwrap() = request["params"].clone();
+                    let response = json!({"jsonrpc": "2.0", "id": request["id"], "result": result});
+                    Ok::<_, Infallible>(Response::new(Body::from(response.to_string())))
+                }
+            }))
+        }
+    });
+    let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service);
+    let url = Url::parse(&format!("http://{}", server.local_addr())).unwrap();
+    tokio::spawn(server);
+    (url, last_params)
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_proof(#[future] katana: Katana, _setup: ()) {
+    use starknet::providers::Provider;
+
+    // Given
+    let katana_provider = katana.eth_provider();
+    let kakarot = *katana_provider.kakarot_deployment();
+    let (url, params) = mock_proof_server(json!({
+        "state_commitment": "0x1",
+        "class_commitment": "0x2",
+        "contract_proof": [{"binary": {"left": "0x3", "right": "0x4"}}],
+        "contract_data": {
+            "class_hash": "0x5",
+            "nonce": "0x0",
+            "root": "0x6",
+            "contract_state_hash_version": "0x0",
+            "storage_proofs": [
+                [{"binary": {"left": "0x7", "right": "0x8"}}],
+                [{"edge": {"child": "0x9", "path": {"value": "0xa", "len": 251}}}],
+            ],
+        },
+    }));
+    let eth_provider =
+        EthDataProvider::new(mock_database().await, katana_provider.starknet_provider().clone(), kakarot)
+            .with_proof_client(StarknetProofClient::new(FailoverTransport::new(
+                url,
+                vec![],
+                FailoverConfig::default(),
+            )));
+    let address = katana.eoa().evm_address().unwrap();
+    let key = B256::with_last_byte(1);
+
+    // When
+    let proof = eth_provider.get_proof(address, vec![key], None).await.unwrap();
+
+    // Then
+    // Each EVM slot is proven by its low and high Starknet slots, at the latest sealed block
+    let low = get_storage_var_address("storage_", &split_u256::<FieldElement>(U256::from_be_bytes(key.0))).unwrap();
+    let block_number = katana_provider.starknet_provider().block_number().await.unwrap();
+    let params = params.lock().unwrap().clone();
+    assert_eq!(params["block_id"], json!({ "block_number": block_number }));
+    assert_eq!(params["contract_address"], json!(starknet_address(&kakarot, address)));
+    assert_eq!(params["keys"], json!([low, low + FieldElement::ONE]));
+
+    let binary = |left: u8, right: u8| {
+        ProofNode::Binary(BinaryNode { left: FieldElement::from(left), right: FieldElement::from(right) }).encode()
+    };
+    let edge = ProofNode::Edge(EdgeNode {
+        child: FieldElement::from(9u8),
+        path: EdgePath { value: FieldElement::from(10u8), len: 251 },
+    })
+    .encode();
+    assert_eq!(proof.address, address);
+    assert_eq!(proof.balance, katana_provider.balance(address, None).await.unwrap());
+    assert_eq!(proof.storage_hash, B256::with_last_byte(6));
+    assert_eq!(proof.account_proof, vec![binary(3, 4)]);
+    assert_eq!(proof.storage_proof.len(), 1);
+    assert_eq!(proof.storage_proof[0].key.0, key);
+    assert_eq!(proof.storage_proof[0].proof, vec![binary(7, 8), edge]);
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "testing")]
+use std::collections::HashMap;
+
+use jsonrpsee::rpc_params;
+use kakarot_rpc::eth_provider::provider::EthereumProvider;
+use kakarot_rpc::eth_rpc::config::RPCConfig;
+use kakarot_rpc::eth_rpc::rpc::KakarotRpcModuleBuilder;
+use kakarot_rpc::eth_rpc::run_multi_chain_server;
+use kakarot_rpc::models::health::HealthReport;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use kakarot_rpc::test_utils::mongo::BLOCK_NUMBER;
+use kakarot_rpc::test_utils::rpc::start_kakarot_rpc_server;
+use rstest::*;
+use serde_json::json;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_provider_health(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let eth_provider = katana.eth_provider();
+
+    // When
+    let health = eth_provider.health().await;
+
+    // Then
+    assert!(health.mongo.healthy);
+    assert_eq!(health.mongo.block_number, Some(*BLOCK_NUMBER));
+    assert!(health.starknet.healthy);
+    assert!(health.starknet.block_number.is_some());
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_net_health(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).with_max_indexer_lag(5).rpc_module().unwrap();
+
+    // When
+    let report: HealthReport = rpc_module.call("net_health", rpc_params![]).await.unwrap();
+
+    // Then
+    // The mocked database is ahead of Katana, so the indexer doesn't lag.
+    assert!(report.healthy);
+    assert_eq!(report.indexer.lag, Some(0));
+    assert_eq!(report.indexer.max_lag, 5);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ready(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let (server_addr, server_handle) =
+        start_kakarot_rpc_server(&katana).await.expect("Error setting up Kakarot RPC server");
+
+    // When
+    let response = reqwest::Client::new()
+        .get(format!("http://localhost:{}/ready", server_addr.port()))
+        .send()
+        .await
+        .expect("Failed to call the readiness endpoint");
+
+    // Then
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    let report: HealthReport =
+        serde_json::from_str(&response.text().await.expect("Failed to get response body")).unwrap();
+    assert!(report.mongo.healthy && report.starknet.healthy && report.indexer.healthy);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multi_chain_metrics_and_ready(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let kakarot_rpc_modules = ["devnet", "staging"]
+        .into_iter()
+        .map(|name| (name.to_string(), KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().unwrap()))
+        .collect::<HashMap<_, _>>();
+    let (server_addr, server_handle) =
+        run_multi_chain_server(kakarot_rpc_modules, RPCConfig::new("127.0.0.1:0".to_string()))
+            .await
+            .expect("Failed to start the server");
+    let client = reqwest::Client::new();
+    client
+        .post(format!("http://{server_addr}/chain/staging"))
+        .header("Content-Type", "application/json")
+        .body(json!({"jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1}).to_string())
+        .send()
+        .await
+        .expect("Failed to call eth_chainId");
+
+    // When
+    let metrics = client.get(format!("http://{server_addr}/metrics")).send().await.expect("Failed to get the metrics");
+    let ready = client
+        .get(format!("http://{server_addr}/chain/devnet/ready"))
+        .send()
+        .await
+        .expect("Failed to get the readiness");
+    let root_ready =
+        client.get(format!("http://{server_addr}/ready")).send().await.expect("Failed to get the readiness");
+
+    // Then
+    assert_eq!(metrics.status(), reqwest::StatusCode::OK);
+    let metrics = metrics.text().await.expect("Failed to get response body");
+    assert!(metrics.contains("kakarot_rpc_requests_total{chain=\"staging\",method=\"eth_chainId\"}"));
+    assert_eq!(ready.status(), reqwest::StatusCode::OK);
+    let report: HealthReport = serde_json::from_str(&ready.text().await.expect("Failed to get response body")).unwrap();
+    assert!(report.healthy);
+    // The health checks are specific to each deployment
+    assert_eq!(root_ready.status(), reqwest::StatusCode::NOT_FOUND);
+    drop(server_handle);
+}
@@ -0,0 +1,114 @@
+#![cfg(feature = "testing")]
+use std::net::SocketAddr;
+
+use kakarot_rpc::eth_provider::error::EthRpcErrorCode;
+use kakarot_rpc::eth_rpc::config::{RPCConfig, RateLimitConfig};
+use kakarot_rpc::eth_rpc::rpc::KakarotRpcModuleBuilder;
+use kakarot_rpc::eth_rpc::run_server;
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use rstest::*;
+use serde_json::{json, Value};
+
+/// Calls `eth_chainId` once per API key, in order, and returns the responses.
+async fn call_with_api_keys(server_addr: SocketAddr, api_keys: &[&str]) -> Vec<Value> {
+    let client = reqwest::Client::new();
+    let mut responses = Vec::new();
+    for api_key in api_keys {
+        let response = client
+            .post(format!("http://{server_addr}"))
+            .header("Content-Type", "application/json")
+            .header("X-Api-Key", *api_key)
+            .body(json!({"jsonrpc": "2.0", "method": "eth_chainId", "params": [], "id": 1}).to_string())
+            .send()
+            .await
+            .expect("Failed to call eth_chainId");
+        let body: Value = serde_json::from_str(&response.text().await.expect("Failed to get response body")).unwrap();
+        responses.push(body);
+    }
+    responses
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().unwrap();
+    let rpc_config = RPCConfig::new("127.0.0.1:0".to_string())
+        .with_rate_limit(RateLimitConfig { burst: 1, ..RateLimitConfig::new(1) });
+    let (server_addr, server_handle) = run_server(rpc_module, rpc_config).await.expect("Failed to start the server");
+
+    // When
+    let responses = call_with_api_keys(server_addr, &["alice", "bob"]).await;
+
+    // Then
+    assert!(responses[0]["result"].is_string());
+    // Without authentication, the API keys aren't verified and the clients are limited by IP
+    assert_eq!(responses[1]["error"]["code"], json!(EthRpcErrorCode::RequestLimitExceeded as i32));
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_websocket(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().unwrap();
+    let rpc_config = RPCConfig::new("127.0.0.1:0".to_string())
+        .with_rate_limit(RateLimitConfig { burst: 1, ..RateLimitConfig::new(1) });
+    let (server_addr, server_handle) = run_server(rpc_module, rpc_config).await.expect("Failed to start the server");
+
+    // When
+    let response = reqwest::Client::new()
+        .get(format!("http://{server_addr}"))
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+        .send()
+        .await
+        .expect("Failed to send the upgrade request");
+
+    // Then
+    // The calls of a WebSocket connection would escape the limits of the client
+    assert_eq!(response.status(), reqwest::StatusCode::FORBIDDEN);
+    drop(server_handle);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rate_limit_authenticated(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let keys_file = std::env::temp_dir().join(format!("kakarot-rpc-rate-limit-{}.toml", std::process::id()));
+    std::fs::write(
+        &keys_file,
+        r#"
+        [[clients]]
+        name = "alice"
+        api_key = "alice-key"
+
+        [[clients]]
+        name = "bob"
+        api_key = "bob-key"
+        "#,
+    )
+    .unwrap();
+    let rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().unwrap();
+    let rpc_config = RPCConfig::new("127.0.0.1:0".to_string())
+        .with_rate_limit(RateLimitConfig { burst: 1, ..RateLimitConfig::new(1) })
+        .with_auth_keys_file(keys_file.clone());
+    let (server_addr, server_handle) = run_server(rpc_module, rpc_config).await.expect("Failed to start the server");
+
+    // When
+    let responses = call_with_api_keys(server_addr, &["alice-key", "alice-key", "bob-key"]).await;
+
+    // Then
+    assert!(responses[0]["result"].is_string());
+    assert_eq!(responses[1]["error"]["code"], json!(EthRpcErrorCode::RequestLimitExceeded as i32));
+    // Other clients aren't limited by alice's requests
+    assert!(responses[2]["result"].is_string());
+    drop(server_handle);
+    std::fs::remove_file(&keys_file).unwrap();
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "testing")]
+use std::collections::BTreeMap;
+
+use jsonrpsee::rpc_params;
+use jsonrpsee::server::RegisterMethodError;
+use kakarot_rpc::eth_rpc::rpc::{KakarotRpcModule, KakarotRpcModuleBuilder, RpcModuleSelection};
+use kakarot_rpc::test_utils::fixtures::{katana, setup};
+use kakarot_rpc::test_utils::katana::Katana;
+use rstest::*;
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_modules_default(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let rpc_module = KakarotRpcModuleBuilder::new(katana.eth_provider()).rpc_module().unwrap();
+
+    // When
+    let modules: BTreeMap<String, String> = rpc_module.call("rpc_modules", rpc_params![]).await.unwrap();
+
+    // Then
+    assert_eq!(modules.into_keys().collect::<Vec<_>>(), vec!["alchemy", "eth", "net", "rpc", "web3"]);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_modules_selection(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let selection = RpcModuleSelection {
+        modules: vec![KakarotRpcModule::Eth, KakarotRpcModule::Net],
+        allowed_methods: vec![],
+        denied_methods: vec!["eth_sendRawTransaction".to_string(), "net_peerCount".to_string()],
+    };
+
+    // When
+    let rpc_module =
+        KakarotRpcModuleBuilder::new(katana.eth_provider()).with_selection(selection).rpc_module().unwrap();
+    let modules: BTreeMap<String, String> = rpc_module.call("rpc_modules", rpc_params![]).await.unwrap();
+
+    // Then
+    assert_eq!(modules.into_keys().collect::<Vec<_>>(), vec!["eth", "net", "rpc"]);
+    let methods = rpc_module.method_names().collect::<Vec<_>>();
+    assert!(methods.contains(&"eth_blockNumber"));
+    assert!(!methods.contains(&"eth_sendRawTransaction"));
+    assert!(!methods.contains(&"net_peerCount"));
+    assert!(!methods.iter().any(|method| method.starts_with("alchemy_") || method.starts_with("web3_")));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_modules_allowed_methods(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let selection = RpcModuleSelection {
+        allowed_methods: vec!["eth_chainId".to_string(), "net_version".to_string()],
+        ..Default::default()
+    };
+
+    // When
+    let rpc_module =
+        KakarotRpcModuleBuilder::new(katana.eth_provider()).with_selection(selection).rpc_module().unwrap();
+
+    // Then
+    let mut methods = rpc_module.method_names().collect::<Vec<_>>();
+    methods.sort_unstable();
+    assert_eq!(methods, vec!["eth_chainId", "net_health", "net_version"]);
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_modules_health_always_served(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let selection = RpcModuleSelection {
+        modules: vec![KakarotRpcModule::Eth],
+        allowed_methods: vec![],
+        denied_methods: vec!["net_health".to_string()],
+    };
+
+    // When
+    let rpc_module =
+        KakarotRpcModuleBuilder::new(katana.eth_provider()).with_selection(selection).rpc_module().unwrap();
+    let modules: BTreeMap<String, String> = rpc_module.call("rpc_modules", rpc_params![]).await.unwrap();
+
+    // Then
+    // The health probes still work, while the net namespace isn't reported
+    assert_eq!(modules.into_keys().collect::<Vec<_>>(), vec!["eth", "rpc"]);
+    let methods = rpc_module.method_names().collect::<Vec<_>>();
+    assert!(methods.contains(&"net_health"));
+    assert!(!methods.contains(&"net_version"));
+}
+
+#[rstest]
+#[awt]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_rpc_modules_unknown_method(#[future] katana: Katana, _setup: ()) {
+    // Given
+    let selection = RpcModuleSelection { denied_methods: vec!["eth_unknown".to_string()], ..Default::default() };
+
+    // When
+    let result = KakarotRpcModuleBuilder::new(katana.eth_provider()).with_selection(selection).rpc_module();
+
+    // Then
+    assert!(matches!(result, Err(RegisterMethodError::MethodNotFound(method)) if method == "eth_unknown"));
+}